@@ -0,0 +1,177 @@
+use crate::error::Error;
+use crate::private_client::PrivateClient;
+use crate::public_client::PublicClient;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::transport::{HeaderMap, ReqwestTransport};
+use crate::{COINBASE_API_URL, COINBASE_SANDBOX_API_URL};
+use reqwest::header::{HeaderValue, USER_AGENT};
+use std::time::Duration;
+
+/// `ClientBuilder` configures the HTTP connection of a `PublicClient` or `PrivateClient`
+/// <br>
+/// The base URL can point at a local mock server or a corporate egress proxy. `connect_timeout` and `proxy` are ignored when a pre-configured `reqwest::Client` is supplied
+/// <br>
+/// ~~~~ignore
+/// let client = ClientBuilder::new()
+///     .base_url("http://localhost:8080")
+///     .timeout(Duration::from_secs(5))
+///     .user_agent("my-trading-bot/1.0")
+///     .build_public()
+///     .unwrap();
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    url: String,
+    client: Option<reqwest::Client>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl ClientBuilder {
+    /// Creates a `ClientBuilder` for the coinbase pro API
+    pub fn new() -> Self {
+        Self {
+            url: COINBASE_API_URL.to_string(),
+            client: None,
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            user_agent: None,
+            headers: HeaderMap::new(),
+            rate_limiter: None,
+            retry_policy: None,
+        }
+    }
+
+    /// Creates a `ClientBuilder` for the coinbase pro sandbox API
+    pub fn sandbox() -> Self {
+        Self::new().base_url(COINBASE_SANDBOX_API_URL)
+    }
+
+    /// Sends requests to `url` instead of the coinbase pro API
+    pub fn base_url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+
+    /// Sets the timeout for connecting to the API
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of each request, from sending it until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Routes requests through `proxy`
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sends requests with a pre-configured `reqwest::Client`
+    pub fn reqwest_client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the `User-Agent` header, `coinbase-client` by default
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Adds `headers` to every request, headers set by the client and `user_agent` take precedence
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Throttles the requests of the built client with `rate_limiter`, see `RateLimiter::public` and `RateLimiter::private`
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Retries requests of the built client that fail with a transient error according to `retry_policy`
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Creates a `PublicClient`
+    pub fn build_public(mut self) -> Result<PublicClient, Error> {
+        let rate_limiter = self.rate_limiter.take();
+        let retry_policy = self.retry_policy.take();
+        let mut client = PublicClient::with_transport(self.build_transport()?);
+        if let Some(rate_limiter) = rate_limiter {
+            client = client.with_rate_limiter(rate_limiter);
+        }
+        if let Some(retry_policy) = retry_policy {
+            client = client.with_retry_policy(retry_policy);
+        }
+        Ok(client)
+    }
+
+    /// Creates a `PrivateClient` that signs its requests with the given credentials, returning an `Auth` error if they cannot sign requests
+    pub fn build_private(
+        mut self,
+        secret: String,
+        passphrase: String,
+        key: String,
+    ) -> Result<PrivateClient, Error> {
+        let rate_limiter = self.rate_limiter.take();
+        let retry_policy = self.retry_policy.take();
+        let mut client =
+            PrivateClient::try_with_transport(secret, passphrase, key, self.build_transport()?)?;
+        if let Some(rate_limiter) = rate_limiter {
+            client = client.with_rate_limiter(rate_limiter);
+        }
+        if let Some(retry_policy) = retry_policy {
+            client = client.with_retry_policy(retry_policy);
+        }
+        Ok(client)
+    }
+
+    /// Creates the `ReqwestTransport` used by the built clients
+    pub fn build_transport(self) -> Result<ReqwestTransport, Error> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        let mut transport = ReqwestTransport::with_client(client, &self.url);
+        // an explicit `user_agent` wins over a `User-Agent` in the default headers
+        transport.headers.extend(self.headers);
+        if let Some(user_agent) = self.user_agent {
+            transport
+                .headers
+                .insert(USER_AGENT, HeaderValue::from_str(&user_agent)?);
+        }
+        transport.timeout = self.timeout;
+        Ok(transport)
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,57 +1,252 @@
+use crate::transport::{Method, Response};
 use serde::Deserialize;
 use serde_json;
 use std::error::Error as StdError;
 use std::fmt;
+use tokio_tungstenite::tungstenite::Error as WebsocketError;
+
+/// The error of a failed request
+/// <br>
+/// `context` describes the request that failed, when the error comes from the REST API
 #[derive(Debug)]
 pub struct Error {
     pub kind: ErrorKind,
+    pub context: Option<Box<RequestContext>>,
 }
 
-impl StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            ErrorKind::HTTP(err) => Some(err),
+            ErrorKind::JSON(err) => Some(err),
+            ErrorKind::Websocket(err) => Some(err.as_ref()),
+            ErrorKind::InvalidHeader(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.kind {
-            ErrorKind::HTTP(_) => {
-                write!(f, "http error")
+            ErrorKind::HTTP(err) => {
+                write!(f, "http error: {}", err)?;
+            }
+            ErrorKind::Status(err)
+            | ErrorKind::InsufficientFunds(err)
+            | ErrorKind::OrderSizeTooSmall(err)
+            | ErrorKind::PostOnly(err) => {
+                write!(f, "status code: {}, message: {}", err.code, err.message)?;
+            }
+            ErrorKind::JSON(err) => {
+                write!(f, "json error: {}", err)?;
+            }
+            ErrorKind::Websocket(err) => {
+                write!(f, "websocket error: {}", err)?;
+            }
+            ErrorKind::InvalidHeader(_) => {
+                write!(f, "invalid header value")?;
+            }
+            ErrorKind::Auth(message) => {
+                write!(f, "auth error: {}", message)?;
             }
-            ErrorKind::Status(err) => {
-                write!(f, "status code: {}, message: {}", err.code, err.message)
+            ErrorKind::Oracle(message) => {
+                write!(f, "oracle error: {}", message)?;
             }
-            ErrorKind::JSON(_) => {
-                write!(f, "json error")
+            ErrorKind::Validation(err) => {
+                write!(f, "validation error: {}", err)?;
             }
         }
+        if let Some(context) = &self.context {
+            write!(f, " ({} {}", context.method, context.path)?;
+            if let Some(request_id) = &context.request_id {
+                write!(f, ", request id: {}", request_id)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
-        Self {
-            kind: ErrorKind::HTTP(e),
-        }
+        Self::new(ErrorKind::HTTP(e))
     }
 }
 
 impl From<serde_json::Error> for Error {
     fn from(e: serde_json::Error) -> Self {
-        Self {
-            kind: ErrorKind::JSON(e),
-        }
+        Self::new(ErrorKind::JSON(e))
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::new(ErrorKind::Websocket(Box::new(e)))
+    }
+}
+
+impl From<reqwest::header::InvalidHeaderValue> for Error {
+    fn from(e: reqwest::header::InvalidHeaderValue) -> Self {
+        Self::new(ErrorKind::InvalidHeader(e))
     }
 }
 
 impl Error {
     pub fn new(kind: ErrorKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            context: None,
+        }
+    }
+
+    pub(crate) fn auth(message: &str) -> Self {
+        Self::new(ErrorKind::Auth(message.to_string()))
+    }
+
+    #[cfg(feature = "oracle")]
+    pub(crate) fn oracle(message: &str) -> Self {
+        Self::new(ErrorKind::Oracle(message.to_string()))
     }
+
+    // creates the error of an unsuccessful response, mapping known messages to their own kind
+    pub(crate) fn from_status(code: u16, message: String) -> Self {
+        let lowercase = message.to_lowercase();
+        let status = StatusError::new(code, message);
+        Self::new(if lowercase.starts_with("insufficient funds") {
+            ErrorKind::InsufficientFunds(status)
+        } else if lowercase.contains("size is too small") {
+            ErrorKind::OrderSizeTooSmall(status)
+        } else if lowercase.starts_with("post only mode") {
+            ErrorKind::PostOnly(status)
+        } else {
+            ErrorKind::Status(status)
+        })
+    }
+
+    // attaches the request and, if there is one, the response that caused the error
+    pub(crate) fn with_context(
+        mut self,
+        method: Method,
+        path: &str,
+        response: Option<&Response>,
+    ) -> Self {
+        self.context = Some(Box::new(RequestContext {
+            method,
+            path: path.to_string(),
+            status: response.map(|response| response.status),
+            body: response.map(|response| response.body.clone()),
+            request_id: response.and_then(|response| {
+                response
+                    .headers
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from)
+            }),
+        }));
+        self
+    }
+
+    /// Gets the HTTP status code of the response, if there was one
+    pub fn status(&self) -> Option<u16> {
+        match &self.kind {
+            ErrorKind::Status(err)
+            | ErrorKind::InsufficientFunds(err)
+            | ErrorKind::OrderSizeTooSmall(err)
+            | ErrorKind::PostOnly(err) => Some(err.code),
+            _ => self.context.as_ref().and_then(|context| context.status),
+        }
+    }
+
+    /// `true` if sending the request again may succeed: rate limits, server errors, timeouts, failed connections
+    /// and connections reset before the response
+    pub fn is_retryable(&self) -> bool {
+        match &self.kind {
+            ErrorKind::HTTP(err) => {
+                err.is_connect() || err.is_timeout() || is_connection_reset(err)
+            }
+            ErrorKind::Websocket(err) => match err.as_ref() {
+                WebsocketError::ConnectionClosed => true,
+                WebsocketError::Io(err) => is_reset_kind(err),
+                _ => false,
+            },
+            _ => matches!(self.status(), Some(code) if code == 429 || code >= 500),
+        }
+    }
+
+    /// `true` if the request exceeded the rate limit
+    pub fn is_rate_limit(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// `true` if the credentials were missing, invalid or lack the permission for the request
+    pub fn is_auth(&self) -> bool {
+        matches!(self.kind, ErrorKind::Auth(_)) || matches!(self.status(), Some(401) | Some(403))
+    }
+
+    /// `true` if the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// `true` if the request was rejected because of its parameters
+    pub fn is_validation(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::InsufficientFunds(_)
+                | ErrorKind::OrderSizeTooSmall(_)
+                | ErrorKind::PostOnly(_)
+                | ErrorKind::Validation(_)
+        ) || matches!(self.status(), Some(400) | Some(422))
+    }
+}
+
+// whether the sources of `err` report a connection dropped before the response
+fn is_connection_reset(err: &(dyn StdError + 'static)) -> bool {
+    let mut source = err.source();
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<hyper::Error>() {
+            if err.is_incomplete_message() || err.is_closed() {
+                return true;
+            }
+        }
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            return is_reset_kind(err);
+        }
+        source = err.source();
+    }
+    false
+}
+
+fn is_reset_kind(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::UnexpectedEof
+    )
 }
 
 #[derive(Debug)]
 pub enum ErrorKind {
     HTTP(reqwest::Error),
     Status(StatusError),
+    /// The account does not hold enough funds for the order or transfer
+    InsufficientFunds(StatusError),
+    /// The order size is below the minimum of the product
+    OrderSizeTooSmall(StatusError),
+    /// The product only accepts post only orders
+    PostOnly(StatusError),
     JSON(serde_json::Error),
+    Websocket(Box<tokio_tungstenite::tungstenite::Error>),
+    InvalidHeader(reqwest::header::InvalidHeaderValue),
+    /// The credentials of a `PrivateClient` cannot sign requests
+    Auth(String),
+    /// The signed prices of `OracleData` cannot be verified
+    Oracle(String),
+    /// The order breaks the trading rules of its product, found before it was sent
+    Validation(ValidationError),
 }
 
 #[derive(Debug)]
@@ -65,7 +260,93 @@ impl StatusError {
         Self { code, message }
     }
 }
+
+/// The trading rule of a `Product` that an `Order` breaks
+/// <br>
+/// `field` is the order parameter that breaks the rule: "price", "size", "funds", "stop_price" or "stop_limit_price".
+/// A string that `FixedDecimal::from_str` cannot parse, or a value that `ToDecimal` cannot convert, is an `InvalidNumber` with the field "decimal"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The order is for another product
+    ProductMismatch { order: String, product: String },
+    /// The product does not accept orders
+    TradingDisabled,
+    /// The product only accepts cancellations
+    CancelOnly,
+    /// The product does not accept market orders
+    LimitOnly,
+    /// The product only accepts post only limit orders
+    PostOnly,
+    /// A parameter is not a decimal number
+    InvalidNumber { field: &'static str, value: String },
+    /// A parameter is not a multiple of the product's increment
+    Increment {
+        field: &'static str,
+        value: String,
+        increment: String,
+    },
+    /// A parameter is below the product's minimum
+    BelowMinimum {
+        field: &'static str,
+        value: String,
+        minimum: String,
+    },
+    /// A parameter is above the product's maximum
+    AboveMaximum {
+        field: &'static str,
+        value: String,
+        maximum: String,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ProductMismatch { order, product } => {
+                write!(f, "order for {} validated against {}", order, product)
+            }
+            Self::TradingDisabled => write!(f, "trading is disabled"),
+            Self::CancelOnly => write!(f, "product is in cancel only mode"),
+            Self::LimitOnly => write!(f, "product is in limit only mode"),
+            Self::PostOnly => write!(f, "product is in post only mode"),
+            Self::InvalidNumber { field, value } => {
+                write!(f, "{} {} is not a number", field, value)
+            }
+            Self::Increment {
+                field,
+                value,
+                increment,
+            } => write!(f, "{} {} is not a multiple of {}", field, value, increment),
+            Self::BelowMinimum {
+                field,
+                value,
+                minimum,
+            } => write!(f, "{} {} is below the minimum of {}", field, value, minimum),
+            Self::AboveMaximum {
+                field,
+                value,
+                maximum,
+            } => write!(f, "{} {} is above the maximum of {}", field, value, maximum),
+        }
+    }
+}
+
+// header with the id that coinbase assigns to each request
+const REQUEST_ID_HEADER: &str = "cb-request-id";
+
+/// A structure that represents the request that caused an `Error`
+/// <br>
+/// `status`, `body` and `request_id` are taken from the response, if there was one
+#[derive(Debug)]
+pub struct RequestContext {
+    pub method: Method,
+    pub path: String,
+    pub status: Option<u16>,
+    pub body: Option<String>,
+    pub request_id: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct ErrorMessage {
-    pub message: String
-}
\ No newline at end of file
+    pub message: String,
+}
@@ -0,0 +1,210 @@
+use crate::error::Error;
+use crate::invalid_decimal;
+use crate::trading_rules::Rounding;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+// most digits of a mantissa, so that rescaling two numbers to a common scale cannot overflow an i128
+const MAX_DIGITS: usize = 18;
+
+/// An exact decimal number with up to 18 significant digits, the prices and sizes of the local order books
+/// <br>
+/// Parsed from strings like `"36000.01"` with `str::parse`, compared, added and subtracted without the errors of float arithmetic
+// `mantissa` / 10^`scale`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedDecimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl FixedDecimal {
+    // parses a plain decimal like "-0.00100000" to the scale of its last nonzero digit, exponents are not supported
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (digits, ""),
+        };
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        // trailing zeros are not significant, "20000000000.00000000" has 11 digits
+        let fraction = fraction.trim_end_matches('0');
+        let significant = format!("{}{}", integer, fraction);
+        let significant = significant.trim_start_matches('0');
+        if significant.len() > MAX_DIGITS || fraction.len() > MAX_DIGITS {
+            return None;
+        }
+        let mantissa = significant.parse::<i128>().unwrap_or(0);
+        Some(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fraction.len() as u32,
+        })
+    }
+
+    // parses a number like `parse`, dropping the decimal places past the supported precision
+    // the string of a small float can have more, e.g. "0.0027736173297830477"
+    pub(crate) fn parse_truncated(value: &str) -> Option<Self> {
+        match value.split_once('.') {
+            Some((integer, fraction))
+                if fraction.len() > MAX_DIGITS && fraction.is_char_boundary(MAX_DIGITS) =>
+            {
+                Self::parse(&format!("{}.{}", integer, &fraction[..MAX_DIGITS]))
+            }
+            _ => Self::parse(value),
+        }
+    }
+
+    // the same number without trailing zeros in its decimal places, "0.01000000" becomes "0.01"
+    pub(crate) fn normalize(mut self) -> Self {
+        while self.scale > 0 && self.mantissa % 10 == 0 {
+            self.mantissa /= 10;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    /// Whether the number is zero
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub(crate) fn is_positive(&self) -> bool {
+        self.mantissa > 0
+    }
+
+    // the mantissa of the number with `scale` decimal places, which must not be below `self.scale`
+    fn mantissa_at(&self, scale: u32) -> i128 {
+        self.mantissa * 10i128.pow(scale - self.scale)
+    }
+
+    // the multiple of `increment` next to the number in the direction of `rounding`, with the scale of `increment`
+    pub(crate) fn round_to(&self, increment: &Self, rounding: Rounding) -> Self {
+        if increment.mantissa <= 0 {
+            return *self;
+        }
+        let scale = self.scale.max(increment.scale);
+        let step = increment.mantissa_at(scale);
+        let value = self.mantissa_at(scale);
+        let (quotient, remainder) = (value.div_euclid(step), value.rem_euclid(step));
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => remainder > 0,
+            Rounding::Nearest => remainder * 2 >= step,
+        };
+        Self {
+            mantissa: (quotient + round_up as i128) * increment.mantissa,
+            scale: increment.scale,
+        }
+    }
+
+    // whether the number is a whole multiple of `increment`, any number is a multiple of zero
+    pub(crate) fn is_multiple_of(&self, increment: &Self) -> bool {
+        let scale = self.scale.max(increment.scale);
+        increment.is_zero() || self.mantissa_at(scale) % increment.mantissa_at(scale) == 0
+    }
+}
+
+// sums and differences have the larger scale of the two numbers
+impl Add for FixedDecimal {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let scale = self.scale.max(other.scale);
+        Self {
+            mantissa: self.mantissa_at(scale) + other.mantissa_at(scale),
+            scale,
+        }
+    }
+}
+
+impl Sub for FixedDecimal {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let scale = self.scale.max(other.scale);
+        Self {
+            mantissa: self.mantissa_at(scale) - other.mantissa_at(scale),
+            scale,
+        }
+    }
+}
+
+impl Sum for FixedDecimal {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a FixedDecimal> for FixedDecimal {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl FromStr for FixedDecimal {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        Self::parse(value).ok_or_else(|| invalid_decimal(value))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl crate::ToDecimal for FixedDecimal {
+    fn to_decimal(&self) -> Result<rust_decimal::Decimal, Error> {
+        rust_decimal::Decimal::try_from_i128_with_scale(self.mantissa, self.scale)
+            .map_err(|_| invalid_decimal(&self.to_string()))
+    }
+}
+
+impl PartialEq for FixedDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FixedDecimal {}
+
+impl PartialOrd for FixedDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FixedDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.mantissa_at(scale).cmp(&other.mantissa_at(scale))
+    }
+}
+
+impl fmt::Display for FixedDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = format!(
+            "{:0>width$}",
+            self.mantissa.abs(),
+            width = self.scale as usize + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        if self.mantissa < 0 {
+            f.write_str("-")?;
+        }
+        if fraction.is_empty() {
+            f.write_str(integer)
+        } else {
+            write!(f, "{}.{}", integer, fraction)
+        }
+    }
+}
@@ -1,45 +1,285 @@
 //! A library for the Coinbase Pro [API](https://docs.pro.coinbase.com/).
 //!   
 //! **Coinbase Client** is separated into two categories: `PrivateClient` and `PublicClient`. `PrivateClient` requires authentication and provide access to placing orders and other account information. `PublicClient` provides market data and is public.
+//! <br>
+//! Real-time market data is available through `WebsocketFeed` in the `websocket` module.
+//! <br>
+//! Both clients send their requests through a `Transport`, which can be replaced to test without a network.
+//! <br>
+//! `ClientBuilder` configures the base URL, timeouts, proxy and user agent of either client.
+//! <br>
+//! Requests can be throttled to the exchange's rate limits with a `RateLimiter` and retried on transient failures with a `RetryPolicy`.
+//! <br>
+//! `TradingRules` rounds prices and sizes computed as floats to the increments of a product.
+//! <br>
+//! Enable the `decimal` feature to convert prices, sizes and amounts to `rust_decimal::Decimal` with `ToDecimal` and to send a `rust_decimal::Decimal` as an `Amount`.
+//! <br>
+//! Enable the `oracle` feature to verify the signatures of the prices returned by `PrivateClient::oracle`.
+#[macro_use]
+mod macros;
+
+pub mod client_builder;
 pub mod error;
+mod fixed;
 pub mod private_client;
 pub mod public_client;
+pub mod rate_limit;
+pub mod retry;
+pub mod trading_rules;
+pub mod transport;
+pub mod websocket;
+
+pub use self::fixed::FixedDecimal;
 
-use self::error::{Error, ErrorKind, ErrorMessage, StatusError};
+use self::error::{Error, ErrorKind, ErrorMessage, ValidationError};
+use self::transport::{Method, Response};
 use chrono::{DateTime, TimeZone, Utc};
-use serde::{Deserialize, Deserializer};
+use futures::stream::{self, BoxStream};
+use futures::{future, Future, StreamExt, TryStreamExt};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
-pub(crate) const COINBASE_API_URL: &'static str = "https://api.pro.coinbase.com";
-pub(crate) const COINBASE_SANDBOX_API_URL: &'static str =
-    "https://api-public.sandbox.pro.coinbase.com";
+pub(crate) const COINBASE_API_URL: &str = "https://api.pro.coinbase.com";
+pub(crate) const COINBASE_SANDBOX_API_URL: &str = "https://api-public.sandbox.pro.coinbase.com";
+pub(crate) const COINBASE_WEBSOCKET_URL: &str = "wss://ws-feed.pro.coinbase.com";
+pub(crate) const COINBASE_SANDBOX_WEBSOCKET_URL: &str =
+    "wss://ws-feed-public.sandbox.pro.coinbase.com";
 
 /// alias for serde_json::Value return type for data that cannot predictably deserialized into a strongly typed struct
 pub type Json = serde_json::Value;
 
-// deserialize to a type that impls the Deserialize trait
-pub(crate) async fn deserialize_response<T>(response: reqwest::Response) -> Result<T, Error>
+/// An amount of money sent to the API, such as the amount of a withdrawal or the price of an order
+/// <br>
+/// Created from a `&str` or `String` like `"10.00"`, which is sent as is, or from a `rust_decimal::Decimal` with the `decimal` feature.
+/// Amounts are sent as JSON strings so that no precision is lost, round a computed `f64` with `TradingRules` instead of sending it as is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Amount(String);
+
+impl Amount {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl From<&str> for Amount {
+    fn from(amount: &str) -> Self {
+        Self(amount.to_string())
+    }
+}
+
+impl From<String> for Amount {
+    fn from(amount: String) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<&String> for Amount {
+    fn from(amount: &String) -> Self {
+        Self(amount.clone())
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Amount {
+    fn from(amount: rust_decimal::Decimal) -> Self {
+        Self(amount.to_string())
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<&rust_decimal::Decimal> for Amount {
+    fn from(amount: &rust_decimal::Decimal) -> Self {
+        Self(amount.to_string())
+    }
+}
+
+// converts a price or size to an exact decimal for the order books
+// a value that is not a number is reported like any other malformed message
+pub(crate) fn book_decimal(value: &str) -> Result<FixedDecimal, Error> {
+    FixedDecimal::parse(value).ok_or_else(|| {
+        Error::from(<serde_json::Error as serde::de::Error>::custom(format!(
+            "invalid decimal {}",
+            value
+        )))
+    })
+}
+
+/// Converts prices, sizes and amounts to `rust_decimal::Decimal`, with the `decimal` feature
+/// <br>
+/// Implemented for the `String` and `f64` fields of the models and for `FixedDecimal`. A value that is not a number, or does not fit, is a `ValidationError::InvalidNumber`.
+/// <br>
+/// ~~~~ignore
+/// let product = client.get_product("BTC-USD").await.unwrap();
+/// let increment = product.quote_increment.to_decimal().unwrap();
+/// ~~~~
+#[cfg(feature = "decimal")]
+pub trait ToDecimal {
+    fn to_decimal(&self) -> Result<rust_decimal::Decimal, Error>;
+}
+
+#[cfg(feature = "decimal")]
+impl ToDecimal for str {
+    fn to_decimal(&self) -> Result<rust_decimal::Decimal, Error> {
+        self.parse().map_err(|_| invalid_decimal(self))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl ToDecimal for String {
+    fn to_decimal(&self) -> Result<rust_decimal::Decimal, Error> {
+        self.as_str().to_decimal()
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl ToDecimal for f64 {
+    fn to_decimal(&self) -> Result<rust_decimal::Decimal, Error> {
+        rust_decimal::prelude::FromPrimitive::from_f64(*self)
+            .ok_or_else(|| invalid_decimal(&self.to_string()))
+    }
+}
+
+// the error of a value that is not a decimal number
+pub(crate) fn invalid_decimal(value: &str) -> Error {
+    Error::new(ErrorKind::Validation(ValidationError::InvalidNumber {
+        field: "decimal",
+        value: value.to_string(),
+    }))
+}
+
+// deserialize to a type that impls the Deserialize trait, attaching the request to any error
+pub(crate) fn deserialize_response<T>(
+    method: Method,
+    path: &str,
+    response: Response,
+) -> Result<T, Error>
 where
     T: serde::de::DeserializeOwned,
 {
-    let status = response.status();
-    if !status.is_success() {
-        let error_message = response.json::<ErrorMessage>().await?;
-        return Err(Error::new(ErrorKind::Status(StatusError::new(
-            status.as_u16(),
-            error_message.message,
-        ))));
+    if !response.is_success() {
+        return Err(status_error(method, path, &response));
     }
+    serde_json::from_str(&response.body)
+        .map_err(|err| Error::from(err).with_context(method, path, Some(&response)))
+}
+
+// creates the error of an unsuccessful response
+// the body is usually JSON with a `message`, but may be empty, plain text or an HTML page from a proxy
+pub(crate) fn status_error(method: Method, path: &str, response: &Response) -> Error {
+    let message = match serde_json::from_str::<ErrorMessage>(&response.body) {
+        Ok(error_message) => error_message.message,
+        Err(_) if response.body.trim().is_empty() => reqwest::StatusCode::from_u16(response.status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or_default()
+            .to_string(),
+        Err(_) => response.body.trim().to_string(),
+    };
+    Error::from_status(response.status, message).with_context(method, path, Some(response))
+}
+
+/// A structure that represents a page of a [paginated](https://docs.pro.coinbase.com/#pagination) request
+/// <br>
+/// `before` and `after` are the cursors of the `CB-BEFORE` and `CB-AFTER` headers. Request the next (older) page with `after`
+/// and the previous (newer) page with `before`
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+// deserialize a paginated response, keeping the cursors from the response headers
+pub(crate) fn deserialize_page_response<T>(
+    method: Method,
+    path: &str,
+    response: Response,
+) -> Result<Page<T>, Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let cursor = |name: &str| {
+        response
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let before = cursor("cb-before");
+    let after = cursor("cb-after");
+    Ok(Page {
+        items: deserialize_response(method, path, response)?,
+        before,
+        after,
+    })
+}
 
-    Ok(response.json::<T>().await?)
+// items of a paginated request that can be compared against a stop date
+pub(crate) trait CreatedAt {
+    fn created_at(&self) -> DateTime<Utc>;
+}
+
+// follows the `after` cursor of each page with `fetch` until an empty page is returned,
+// stopping early at the first item created before `until`
+pub(crate) fn stream_pages<'a, T, F, Fut>(
+    mut fetch: F,
+    until: Option<DateTime<Utc>>,
+) -> BoxStream<'a, Result<T, Error>>
+where
+    T: CreatedAt + Send + 'a,
+    F: FnMut(Option<String>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<T>, Error>> + Send + 'a,
+{
+    // `None` once there are no more pages, otherwise the cursor of the next page
+    stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| {
+        let request = cursor.map(&mut fetch);
+        async move {
+            let page = match request {
+                Some(request) => request.await?,
+                None => return Ok(None),
+            };
+            if page.items.is_empty() {
+                return Ok(None);
+            }
+            Ok::<_, Error>(Some((page.items, page.after.map(Some))))
+        }
+    })
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+    .try_take_while(move |item| {
+        let before_until = match until {
+            Some(until) => item.created_at() < until,
+            None => false,
+        };
+        future::ready(Ok(!before_until))
+    })
+    .boxed()
 }
 
 // deserializes a ISO 8601 / RFC 3339 date & time format str to a DateTime<Utc>
+// transfers use the postgres format instead, like "2021-06-18 01:37:48.078524+00"
 pub(crate) fn deserialize_to_date<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
     Utc.datetime_from_str(&s, "%+")
+        .or_else(|_| DateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S%.f%#z").map(DateTime::from))
         .map_err(serde::de::Error::custom)
 }
 
@@ -1,6 +1,11 @@
+pub use oracle::*;
 pub use order::*;
 pub use private_client::*;
+pub use registry::*;
 pub use report::*;
+mod oracle;
 mod order;
+#[allow(clippy::module_inception)]
 mod private_client;
+mod registry;
 mod report;
@@ -0,0 +1,149 @@
+#[cfg(feature = "oracle")]
+use crate::error::Error;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// The address that signs the prices of the Coinbase oracle
+#[cfg(feature = "oracle")]
+pub const COINBASE_ORACLE_REPORTER: &str = "0xfCEAdAFab14d46e20144F48824d0C09B1a03F2BC";
+
+/// A structure that represents the signed prices of the Coinbase oracle
+/// <br>
+/// Each of `messages` is an ABI encoded price signed by the matching entry of `signatures`, ready to be posted to an Open Oracle contract.
+/// `prices` are the same prices as decimals, which are not signed. With the `oracle` feature, `verify` checks the signatures and decodes the messages
+#[derive(Debug, Deserialize)]
+pub struct OracleData {
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub messages: Vec<String>,
+    pub signatures: Vec<String>,
+    pub prices: HashMap<String, String>,
+}
+
+/// A structure that represents a price decoded from a signed oracle message
+/// <br>
+/// `value` is the price in millionths, e.g. 8846945000 for 8846.945
+#[cfg(feature = "oracle")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub kind: String,
+    pub timestamp: u64,
+    pub key: String,
+    pub value: u64,
+}
+
+#[cfg(feature = "oracle")]
+impl OracleData {
+    /// Checks that every message is signed by the Coinbase oracle and returns the decoded prices
+    pub fn verify(&self) -> Result<Vec<OraclePrice>, Error> {
+        self.verify_reporter(COINBASE_ORACLE_REPORTER)
+    }
+
+    /// Checks that every message is signed by `reporter`, a hex encoded address, and returns the decoded prices
+    pub fn verify_reporter(&self, reporter: &str) -> Result<Vec<OraclePrice>, Error> {
+        if self.messages.len() != self.signatures.len() {
+            return Err(Error::oracle("number of messages and signatures differ"));
+        }
+        let reporter = decode_hex(reporter)?;
+        self.messages
+            .iter()
+            .zip(&self.signatures)
+            .map(|(message, signature)| {
+                let message = decode_hex(message)?;
+                if recover_signer(&message, &decode_hex(signature)?)?[..] != reporter[..] {
+                    return Err(Error::oracle("message is not signed by the reporter"));
+                }
+                decode_price(&message)
+            })
+            .collect()
+    }
+}
+
+// the oracle sends the unix timestamp as a string
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    let seconds = s.parse::<i64>().map_err(serde::de::Error::custom)?;
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .ok_or_else(|| serde::de::Error::custom(format!("timestamp out of range: {}", s)))
+}
+
+#[cfg(feature = "oracle")]
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|_| Error::oracle("invalid hex"))
+}
+
+#[cfg(feature = "oracle")]
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::Hasher;
+    let mut keccak = tiny_keccak::Keccak::v256();
+    let mut hash = [0; 32];
+    keccak.update(data);
+    keccak.finalize(&mut hash);
+    hash
+}
+
+// recovers the address that signed the ethereum signed message hash of `message`
+// the signature is the ABI encoding of r, s and v
+#[cfg(feature = "oracle")]
+fn recover_signer(message: &[u8], signature: &[u8]) -> Result<[u8; 20], Error> {
+    if signature.len() != 96 || signature[64..95].iter().any(|byte| *byte != 0) {
+        return Err(Error::oracle("invalid signature"));
+    }
+    let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+    prefixed.extend_from_slice(&keccak256(message));
+    let digest = libsecp256k1::Message::parse(&keccak256(&prefixed));
+    let mut rs = [0; 64];
+    rs.copy_from_slice(&signature[..64]);
+    let recovery_id = libsecp256k1::RecoveryId::parse_rpc(signature[95])
+        .map_err(|_| Error::oracle("invalid signature"))?;
+    let signature = libsecp256k1::Signature::parse_standard(&rs)
+        .map_err(|_| Error::oracle("invalid signature"))?;
+    let public_key = libsecp256k1::recover(&digest, &signature, &recovery_id)
+        .map_err(|_| Error::oracle("cannot recover the signer"))?;
+    let hash = keccak256(&public_key.serialize()[1..]);
+    let mut address = [0; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+// decodes a message, the ABI encoding of (string kind, uint64 timestamp, string key, uint64 value)
+#[cfg(feature = "oracle")]
+fn decode_price(message: &[u8]) -> Result<OraclePrice, Error> {
+    let word = |index: usize| read_u64(message, index * 32);
+    Ok(OraclePrice {
+        kind: read_string(message, word(0)? as usize)?,
+        timestamp: word(1)?,
+        key: read_string(message, word(2)? as usize)?,
+        value: word(3)?,
+    })
+}
+
+#[cfg(feature = "oracle")]
+fn read_u64(message: &[u8], offset: usize) -> Result<u64, Error> {
+    let word = offset
+        .checked_add(32)
+        .and_then(|end| message.get(offset..end))
+        .ok_or_else(|| Error::oracle("invalid message"))?;
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return Err(Error::oracle("invalid message"));
+    }
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&word[24..]);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(feature = "oracle")]
+fn read_string(message: &[u8], offset: usize) -> Result<String, Error> {
+    let len = read_u64(message, offset)? as usize;
+    let start = offset + 32;
+    let bytes = start
+        .checked_add(len)
+        .and_then(|end| message.get(start..end))
+        .ok_or_else(|| Error::oracle("invalid message"))?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| Error::oracle("invalid message"))
+}
@@ -1,26 +1,137 @@
+use crate::error::{Error, ErrorKind, ValidationError};
+use crate::fixed::FixedDecimal;
+use crate::public_client::Product;
+use crate::trading_rules::{Rounding, TradingRules};
+use crate::Amount;
 use serde::Serialize;
 
 /// A `OrderBuilder` should be used to create a `Order` with  custom configuration.
 #[derive(Serialize, Debug)]
 pub struct Order {
-    r#type: String,
+    r#type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     price: Option<String>,
     side: OrderSide,
+    #[serde(skip_serializing_if = "Option::is_none")]
     client_oid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     self_trade_prevention: Option<SelfTradePrevention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     cancel_after: Option<CancelAfter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     funds: Option<String>,
     product_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stop: Option<OrderStop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stop_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_limit_price: Option<String>,
 }
 
 /// A `OrderBuilder` should be used to create a `Order` with  custom configuration.
 impl Order {
+    /// Gets the client_oid of the order, if any
+    pub fn client_oid(&self) -> Option<&str> {
+        self.client_oid.as_deref()
+    }
+
+    // the client_oid of the order, assigning a random one if it has none
+    pub(crate) fn ensure_client_oid(&mut self) -> &str {
+        self.client_oid.get_or_insert_with(new_client_oid)
+    }
+
+    /// Gets the product of the order
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    /// Checks the order against the trading rules of `product` before it is sent
+    /// <br>
+    /// Returns a `Validation` error with the first rule the order breaks: the trading status of the product, the increments of the price, stop price, size and funds,
+    /// the minimum and maximum size and the minimum and maximum funds of market orders
+    /// <br>
+    /// ~~~~ignore
+    /// let product = PublicClient::new().get_product("BTC-USD").await.unwrap();
+    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.005", "1.0").build();
+    /// assert!(order.validate(&product).is_err());
+    /// ~~~~
+    pub fn validate(&self, product: &Product) -> Result<(), Error> {
+        self.check_rules(product)
+            .map_err(|err| Error::new(ErrorKind::Validation(err)))
+    }
+
+    fn check_rules(&self, product: &Product) -> Result<(), ValidationError> {
+        if self.product_id != product.id {
+            return Err(ValidationError::ProductMismatch {
+                order: self.product_id.clone(),
+                product: product.id.clone(),
+            });
+        }
+        if product.trading_disabled {
+            return Err(ValidationError::TradingDisabled);
+        }
+        if product.cancel_only {
+            return Err(ValidationError::CancelOnly);
+        }
+        let market = self.r#type == OrderType::Market;
+        if market && product.limit_only {
+            return Err(ValidationError::LimitOnly);
+        }
+        if product.post_only && (market || self.post_only != Some(true)) {
+            return Err(ValidationError::PostOnly);
+        }
+        let prices = [
+            ("price", &self.price),
+            ("stop_price", &self.stop_price),
+            ("stop_limit_price", &self.stop_limit_price),
+        ];
+        for (field, price) in prices.iter() {
+            if let Some(price) = price {
+                let value = parameter(field, price)?;
+                check_increment(field, price, &value, &product.quote_increment)?;
+                if !value.is_positive() {
+                    return Err(ValidationError::BelowMinimum {
+                        field,
+                        value: price.clone(),
+                        minimum: product.quote_increment.to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(size) = &self.size {
+            let value = parameter("size", size)?;
+            check_increment("size", size, &value, &product.base_increment)?;
+            check_range(
+                "size",
+                size,
+                &value,
+                &product.base_min_size,
+                &product.base_max_size,
+            )?;
+        }
+        if let Some(funds) = &self.funds {
+            let value = parameter("funds", funds)?;
+            check_increment("funds", funds, &value, &product.quote_increment)?;
+            check_range(
+                "funds",
+                funds,
+                &value,
+                &product.min_market_funds,
+                &product.max_market_funds,
+            )?;
+        }
+        Ok(())
+    }
+
     /// returns a `OrderBuilder` with required market-order parameters, equivalent OrderBuilder::market
     pub fn market_builder(
         side: OrderSide,
@@ -28,7 +139,7 @@ impl Order {
         size_or_funds: SizeOrFunds,
     ) -> impl SharedOptions {
         OrderBuilder {
-            r#type: "market".to_string(),
+            r#type: OrderType::Market,
             size: match size_or_funds {
                 SizeOrFunds::Size(ref n) => Some(n.to_owned()),
                 _ => None,
@@ -48,6 +159,7 @@ impl Order {
             stp: None,
             stop: None,
             stop_price: None,
+            stop_limit_price: None,
         }
     }
 
@@ -55,14 +167,14 @@ impl Order {
     pub fn limit_builder(
         side: OrderSide,
         product_id: &str,
-        price: &str,
-        size: &str,
+        price: impl Into<Amount>,
+        size: impl Into<Amount>,
     ) -> impl LimitOptions + SharedOptions {
         OrderBuilder {
-            r#type: "limit".to_string(),
-            size: Some(size.to_owned()),
-            price: Some(price.to_owned()),
-            side: side,
+            r#type: OrderType::Limit,
+            size: Some(size.into().to_string()),
+            price: Some(price.into().to_string()),
+            side,
             client_oid: None,
             self_trade_prevention: None,
             time_in_force: None,
@@ -73,42 +185,116 @@ impl Order {
             stp: None,
             stop: None,
             stop_price: None,
+            stop_limit_price: None,
         }
     }
 
     /// returns a `OrderBuilder` with required stop-order parameters, equivalent OrderBuilder::stop
+    #[deprecated(note = "use `Order::stop_limit_builder` or `Order::stop_market_builder`")]
     pub fn stop_builder(
         side: OrderSide,
         product_id: &str,
-        price: &str,
-        size: String,
-        stop_price: &str,
+        price: impl Into<Amount>,
+        size: impl Into<Amount>,
+        stop_price: impl Into<Amount>,
         stop: OrderStop,
     ) -> impl SharedOptions {
-        OrderBuilder {
-            r#type: "limit".to_string(),
-            size: Some(size),
-            price: Some(price.to_owned()),
-            side: side,
-            client_oid: None,
-            self_trade_prevention: None,
-            time_in_force: None,
-            cancel_after: None,
-            post_only: None,
-            funds: None,
-            product_id: product_id.to_string(),
-            stp: None,
-            stop: Some(stop),
-            stop_price: Some(stop_price.to_owned()),
-        }
+        OrderBuilder::stop_limit(side, product_id, stop, stop_price, price, size)
     }
+
+    /// returns a `OrderBuilder` with required stop-limit-order parameters, equivalent OrderBuilder::stop_limit
+    pub fn stop_limit_builder(
+        side: OrderSide,
+        product_id: &str,
+        stop: OrderStop,
+        stop_price: impl Into<Amount>,
+        stop_limit_price: impl Into<Amount>,
+        size: impl Into<Amount>,
+    ) -> impl SharedOptions {
+        OrderBuilder::stop_limit(side, product_id, stop, stop_price, stop_limit_price, size)
+    }
+
+    /// returns a `OrderBuilder` with required stop-market-order parameters, equivalent OrderBuilder::stop_market
+    pub fn stop_market_builder(
+        side: OrderSide,
+        product_id: &str,
+        stop: OrderStop,
+        stop_price: impl Into<Amount>,
+        size_or_funds: SizeOrFunds,
+    ) -> impl SharedOptions {
+        OrderBuilder::stop_market(side, product_id, stop, stop_price, size_or_funds)
+    }
+}
+
+// parses a parameter of an order or a trading rule of a product
+fn parameter(field: &'static str, value: &str) -> Result<FixedDecimal, ValidationError> {
+    FixedDecimal::parse(value).ok_or_else(|| ValidationError::InvalidNumber {
+        field,
+        value: value.to_string(),
+    })
+}
+
+fn new_client_oid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+// replaces a price or size of an order with its rounded value
+fn round_field(
+    value: &mut Option<String>,
+    round: impl Fn(&String) -> Result<Amount, Error>,
+) -> Result<(), Error> {
+    if let Some(value) = value.as_mut() {
+        *value = round(value)?.to_string();
+    }
+    Ok(())
+}
+
+fn check_increment(
+    field: &'static str,
+    text: &str,
+    value: &FixedDecimal,
+    increment: &str,
+) -> Result<(), ValidationError> {
+    if value.is_multiple_of(&parameter(field, increment)?) {
+        Ok(())
+    } else {
+        Err(ValidationError::Increment {
+            field,
+            value: text.to_string(),
+            increment: increment.to_string(),
+        })
+    }
+}
+
+fn check_range(
+    field: &'static str,
+    text: &str,
+    value: &FixedDecimal,
+    minimum: &str,
+    maximum: &str,
+) -> Result<(), ValidationError> {
+    if *value < parameter(field, minimum)? {
+        return Err(ValidationError::BelowMinimum {
+            field,
+            value: text.to_string(),
+            minimum: minimum.to_string(),
+        });
+    }
+    if *value > parameter(field, maximum)? {
+        return Err(ValidationError::AboveMaximum {
+            field,
+            value: text.to_string(),
+            maximum: maximum.to_string(),
+        });
+    }
+    Ok(())
 }
 
 /// A `OrderBuilder` can be used to create a `Order` with custom configuration.
 /// <br>
 /// Configuration parameters details can be found [here](https://docs.pro.coinbase.com/#orders)
 pub struct OrderBuilder {
-    r#type: String,
+    r#type: OrderType,
     size: Option<String>,
     price: Option<String>,
     side: OrderSide,
@@ -122,6 +308,7 @@ pub struct OrderBuilder {
     stp: Option<String>,
     stop: Option<OrderStop>,
     stop_price: Option<String>,
+    stop_limit_price: Option<String>,
 }
 
 impl OrderBuilder {
@@ -132,7 +319,7 @@ impl OrderBuilder {
         size_or_funds: SizeOrFunds,
     ) -> impl SharedOptions {
         Self {
-            r#type: "market".to_string(),
+            r#type: OrderType::Market,
             size: match size_or_funds {
                 SizeOrFunds::Size(ref n) => Some(n.to_owned()),
                 _ => None,
@@ -152,6 +339,7 @@ impl OrderBuilder {
             stp: None,
             stop: None,
             stop_price: None,
+            stop_limit_price: None,
         }
     }
 
@@ -159,14 +347,14 @@ impl OrderBuilder {
     pub fn limit(
         side: OrderSide,
         product_id: &str,
-        price: &str,
-        size: &str,
+        price: impl Into<Amount>,
+        size: impl Into<Amount>,
     ) -> impl LimitOptions + SharedOptions {
         Self {
-            r#type: "limit".to_string(),
-            size: Some(size.to_owned()),
-            price: Some(price.to_owned()),
-            side: side,
+            r#type: OrderType::Limit,
+            size: Some(size.into().to_string()),
+            price: Some(price.into().to_string()),
+            side,
             client_oid: None,
             self_trade_prevention: None,
             time_in_force: None,
@@ -177,23 +365,40 @@ impl OrderBuilder {
             stp: None,
             stop: None,
             stop_price: None,
+            stop_limit_price: None,
         }
     }
 
     /// returns a `OrderBuilder` with required stop-order parameters.
+    #[deprecated(note = "use `OrderBuilder::stop_limit` or `OrderBuilder::stop_market`")]
     pub fn stop(
         side: OrderSide,
         product_id: &str,
-        price: &str,
-        size: &str,
-        stop_price: &str,
+        price: impl Into<Amount>,
+        size: impl Into<Amount>,
+        stop_price: impl Into<Amount>,
         stop: OrderStop,
+    ) -> impl SharedOptions {
+        Self::stop_limit(side, product_id, stop, stop_price, price, size)
+    }
+
+    /// returns a `OrderBuilder` with required stop-limit-order parameters.
+    /// <br>
+    /// Once the last trade price reaches `stop_price`, a limit order for `size` at `stop_limit_price` is placed.
+    /// It is sent as a `stop` order with the limit price in `stop_limit_price`
+    pub fn stop_limit(
+        side: OrderSide,
+        product_id: &str,
+        stop: OrderStop,
+        stop_price: impl Into<Amount>,
+        stop_limit_price: impl Into<Amount>,
+        size: impl Into<Amount>,
     ) -> impl SharedOptions {
         Self {
-            r#type: "limit".to_string(),
-            size: Some(size.to_owned()),
-            price: Some(price.to_owned()),
-            side: side,
+            r#type: OrderType::Stop,
+            size: Some(size.into().to_string()),
+            price: None,
+            side,
             client_oid: None,
             self_trade_prevention: None,
             time_in_force: None,
@@ -203,7 +408,41 @@ impl OrderBuilder {
             product_id: product_id.to_string(),
             stp: None,
             stop: Some(stop),
-            stop_price: Some(stop_price.to_owned()),
+            stop_price: Some(stop_price.into().to_string()),
+            stop_limit_price: Some(stop_limit_price.into().to_string()),
+        }
+    }
+
+    /// returns a `OrderBuilder` with required stop-market-order parameters.
+    /// <br>
+    /// Once the last trade price reaches `stop_price`, a market order for the size or funds is placed
+    pub fn stop_market(
+        side: OrderSide,
+        product_id: &str,
+        stop: OrderStop,
+        stop_price: impl Into<Amount>,
+        size_or_funds: SizeOrFunds,
+    ) -> impl SharedOptions {
+        let (size, funds) = match size_or_funds {
+            SizeOrFunds::Size(size) => (Some(size), None),
+            SizeOrFunds::Funds(funds) => (None, Some(funds)),
+        };
+        Self {
+            r#type: OrderType::Market,
+            size,
+            price: None,
+            side,
+            client_oid: None,
+            self_trade_prevention: None,
+            time_in_force: None,
+            cancel_after: None,
+            post_only: None,
+            funds,
+            product_id: product_id.to_string(),
+            stp: None,
+            stop: Some(stop),
+            stop_price: Some(stop_price.into().to_string()),
+            stop_limit_price: None,
         }
     }
 }
@@ -212,6 +451,10 @@ impl OrderBuilder {
 pub trait SharedOptions {
     fn self_trade_prevention(self, self_trade_prevention: SelfTradePrevention) -> Self;
     fn client_oid(self, client_oid: String) -> Self;
+    fn generate_client_oid(self) -> Self;
+    fn round_to(self, rules: TradingRules) -> Result<Self, Error>
+    where
+        Self: Sized;
     fn build(self) -> Order;
 }
 
@@ -235,6 +478,32 @@ impl SharedOptions for OrderBuilder {
         self
     }
 
+    /// Sets the client_oid of the Order to a random UUIDv4
+    /// <br>
+    /// Read it back with `Order::client_oid` after `build`
+    fn generate_client_oid(mut self) -> Self {
+        self.client_oid = Some(new_client_oid());
+        self
+    }
+
+    /// Rounds the prices, size and funds of the Order to the increments of `rules`
+    /// <br>
+    /// The price and stop limit price are rounded toward the passive side of the book, the stop price to the nearest increment and the size and funds down.
+    /// Returns a `Validation` error if one of them is not a number
+    fn round_to(mut self, rules: TradingRules) -> Result<Self, Error> {
+        let passive = Rounding::passive(&self.side);
+        round_field(&mut self.price, |price| rules.round_price(price, passive))?;
+        round_field(&mut self.stop_limit_price, |price| {
+            rules.round_price(price, passive)
+        })?;
+        round_field(&mut self.stop_price, |stop_price| {
+            rules.round_price(stop_price, Rounding::Nearest)
+        })?;
+        round_field(&mut self.size, |size| rules.round_size(size))?;
+        round_field(&mut self.funds, |funds| rules.round_funds(funds))?;
+        Ok(self)
+    }
+
     /// Builds `Order`
     fn build(self) -> Order {
         Order {
@@ -252,6 +521,7 @@ impl SharedOptions for OrderBuilder {
             stp: self.stp,
             stop: self.stop,
             stop_price: self.stop_price,
+            stop_limit_price: self.stop_limit_price,
         }
     }
 }
@@ -280,19 +550,53 @@ impl LimitOptions for OrderBuilder {
     }
 }
 
-/// Buy or Sell `Order`
-#[derive(Clone, Copy, Debug)]
-pub enum OrderSide {
-    Buy,
-    Sell,
+string_enum! {
+    /// Buy or Sell `Order`
+    pub enum OrderSide {
+        Buy => "buy",
+        Sell => "sell",
+    }
 }
-/// Loss triggers when the last trade price changes to a value at or below the stop_price.
-/// <br>
-/// Entry triggers when the last trade price changes to a value at or above the stop_price.
-#[derive(Clone, Copy, Debug)]
-pub enum OrderStop {
-    Loss,
-    Entry,
+
+string_enum! {
+    /// The type of an order in `OrderInfo`
+    pub enum OrderType {
+        Limit => "limit",
+        Market => "market",
+        Stop => "stop",
+    }
+}
+
+string_enum! {
+    /// The status of an order in `OrderInfo`
+    pub enum OrderState {
+        Received => "received",
+        Open => "open",
+        Pending => "pending",
+        Active => "active",
+        Done => "done",
+        Rejected => "rejected",
+    }
+}
+
+string_enum! {
+    /// The time in force policy of an order in `OrderInfo`
+    pub enum OrderTimeInForce {
+        GoodTillCancel => "GTC",
+        GoodTillTime => "GTT",
+        ImmediateOrCancel => "IOC",
+        FillOrKill => "FOK",
+    }
+}
+
+string_enum! {
+    /// Loss triggers when the last trade price changes to a value at or below the stop_price.
+    /// <br>
+    /// Entry triggers when the last trade price changes to a value at or above the stop_price.
+    pub enum OrderStop {
+        Loss => "loss",
+        Entry => "entry",
+    }
 }
 
 /// Size or Funds of Currency
@@ -302,6 +606,18 @@ pub enum SizeOrFunds {
     Funds(String),
 }
 
+impl SizeOrFunds {
+    /// The amount of base currency to buy or sell
+    pub fn size(size: impl Into<Amount>) -> Self {
+        Self::Size(size.into().to_string())
+    }
+
+    /// The amount of quote currency to spend or receive
+    pub fn funds(funds: impl Into<Amount>) -> Self {
+        Self::Funds(funds.into().to_string())
+    }
+}
+
 // Time in force policies provide guarantees about the lifetime of an `Order`
 #[derive(Clone, Copy, Debug)]
 pub enum TimeInForce {
@@ -346,18 +662,6 @@ impl serde::Serialize for SelfTradePrevention {
     }
 }
 
-impl serde::Serialize for OrderStop {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::ser::Serializer,
-    {
-        match self {
-            Self::Loss => serializer.serialize_str("loss"),
-            Self::Entry => serializer.serialize_str("entry"),
-        }
-    }
-}
-
 impl serde::Serialize for TimeInForce {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -388,18 +692,6 @@ impl serde::Serialize for CancelAfter {
     }
 }
 
-impl serde::Serialize for OrderSide {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::ser::Serializer,
-    {
-        match self {
-            Self::Buy => serializer.serialize_str("buy"),
-            Self::Sell => serializer.serialize_str("sell"),
-        }
-    }
-}
-
 impl serde::Serialize for SizeOrFunds {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1,29 +1,50 @@
 use crate::configure_pagination;
+use crate::websocket::{DoneReason, WebsocketAuth};
 use crate::{
-    deserialize_option_to_date, deserialize_response, deserialize_to_date, Json, COINBASE_API_URL,
-    COINBASE_SANDBOX_API_URL,
+    deserialize_option_to_date, deserialize_page_response, deserialize_response,
+    deserialize_to_date, status_error, stream_pages, Amount, CreatedAt, Json, Page,
+    COINBASE_API_URL, COINBASE_SANDBOX_API_URL,
 };
 
-use super::Order;
 use super::Report;
+use super::{
+    CancelAfter, LimitOptions, OracleData, Order, OrderBuilder, OrderSide, OrderState, OrderStop,
+    OrderTimeInForce, OrderType, SelfTradePrevention, SharedOptions, TimeInForce,
+};
 
-use crate::error::{Error, ErrorKind, ErrorMessage, StatusError};
+use crate::error::{Error, ErrorKind, ValidationError};
+use crate::fixed::FixedDecimal;
+use crate::public_client::Product;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{is_transient, send_with_retry, RetryPolicy};
+use crate::transport::{Method, Request, ReqwestTransport, Response, Transport};
 use base64;
 use chrono::{DateTime, Utc};
-use core::f64;
 use crypto::{self, mac::Mac};
+use futures::stream::BoxStream;
 use reqwest;
 use serde::{self, Deserialize};
+use std::collections::HashMap;
 use std::str;
-use std::time::{SystemTime, SystemTimeError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long `PrivateClient::place_validated_order` uses a product before fetching it again
+pub const PRODUCT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+// products fetched by `place_validated_order`, with the time they were fetched
+type ProductCache = HashMap<String, (Instant, Arc<Product>)>;
 
 /// `PrivateClient` requires authentication and provide access to placing orders and other account information
+#[derive(Clone)]
 pub struct PrivateClient {
-    reqwest_client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
+    products: Arc<Mutex<ProductCache>>,
     secret: String,
     passphrase: String,
     key: String,
-    url: &'static str,
 }
 
 impl PrivateClient {
@@ -33,26 +54,28 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<T, Error>
+    ) -> Result<Page<T>, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let pagination_params = configure_pagination(before, after, limit);
-        self.get(&format!("{}{}", path, pagination_params)).await
+        let path = format!("{}{}", path, configure_pagination(before, after, limit));
+        let response = self.get_response(&path).await?;
+        deserialize_page_response::<T>(Method::GET, &path, response)
     }
 
     async fn get<T>(&self, path: &str) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let headers = self.access_headers(path, None, "GET");
-        let response = self
-            .reqwest_client
-            .get(format!("{}{}", self.url, path))
-            .headers(headers)
-            .send()
-            .await?;
-        deserialize_response::<T>(response).await
+        deserialize_response::<T>(Method::GET, path, self.get_response(path).await?)
+    }
+
+    // GET requests are idempotent and retried according to the retry policy
+    async fn get_response(&self, path: &str) -> Result<Response, Error> {
+        send_with_retry(self.retry_policy.as_ref(), || {
+            self.send(Method::GET, path, None)
+        })
+        .await
     }
 
     async fn post_and_deserialize<T, K>(&self, path: &str, body: Option<K>) -> Result<T, Error>
@@ -60,105 +83,134 @@ impl PrivateClient {
         K: serde::Serialize,
         T: serde::de::DeserializeOwned,
     {
-        deserialize_response::<T>(self.post(path, body).await?).await
+        deserialize_response::<T>(Method::POST, path, self.post(path, body).await?)
     }
 
-    async fn post<K>(&self, path: &str, body: Option<K>) -> Result<reqwest::Response, Error>
+    async fn post<K>(&self, path: &str, body: Option<K>) -> Result<Response, Error>
     where
         K: serde::Serialize,
     {
-        let request_builder = self.reqwest_client.post(format!("{}{}", self.url, path));
-        Ok(if let Some(n) = body {
-            request_builder
-                .headers(self.access_headers(path, Some(&serde_json::to_string(&n)?), "POST"))
-                .json::<K>(&n)
-                .send()
-        } else {
-            request_builder
-                .headers(self.access_headers(path, None, "POST"))
-                .send()
-        }
-        .await?)
+        let body = match body {
+            Some(n) => Some(serde_json::to_string(&n)?),
+            None => None,
+        };
+        self.send(Method::POST, path, body).await
     }
 
     async fn delete<T>(&self, path: &str) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let headers = self.access_headers(path, None, "DELETE");
-        let response = self
-            .reqwest_client
-            .delete(format!("{}{}", self.url, path))
-            .headers(headers)
-            .send()
-            .await?;
-        deserialize_response::<T>(response).await
+        deserialize_response::<T>(
+            Method::DELETE,
+            path,
+            self.send(Method::DELETE, path, None).await?,
+        )
     }
 
-    fn get_current_timestamp() -> Result<String, SystemTimeError> {
+    // signs and sends a request through the transport
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<Response, Error> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let mut headers = self
+            .access_headers(path, body.as_deref(), method.as_str())
+            .map_err(|err| err.with_context(method.clone(), path, None))?;
+        if body.is_some() {
+            headers.insert(
+                reqwest::header::CONTENT_TYPE,
+                reqwest::header::HeaderValue::from_static("application/json"),
+            );
+        }
+        self.transport
+            .send(Request {
+                method: method.clone(),
+                path: path.to_string(),
+                headers,
+                body,
+            })
+            .await
+            .map_err(|err| err.with_context(method, path, None))
+    }
+
+    fn get_current_timestamp() -> Result<String, Error> {
         Ok(SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| Error::auth("system clock is set before the unix epoch"))?
             .as_secs()
             .to_string())
     }
 
+    // checks that the credentials can sign requests, so that signing cannot fail later on
+    fn validate_credentials(secret: &str, passphrase: &str, key: &str) -> Result<(), Error> {
+        base64::decode(secret).map_err(|_| {
+            Error::auth("secret is not valid base64, is your secret in base 64 encoding")
+        })?;
+        header_value(key, "cb-access-key")?;
+        header_value(passphrase, "cb-access-passphrase")?;
+        Ok(())
+    }
+
     fn access_headers(
         &self,
         url: &str,
         body: Option<&str>,
         method: &str,
-    ) -> reqwest::header::HeaderMap {
-        let timestamp = PrivateClient::get_current_timestamp().unwrap();
-        let signature = self.sign_message(url, body, &timestamp, method);
+    ) -> Result<reqwest::header::HeaderMap, Error> {
+        let timestamp = PrivateClient::get_current_timestamp()?;
+        let signature = self.sign_message(url, body, &timestamp, method)?;
         let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            reqwest::header::HeaderValue::from_str("coinbase-client")
-                .expect("invalid user agent value"),
-        );
         headers.insert(
             reqwest::header::HeaderName::from_static("cb-access-key"),
-            reqwest::header::HeaderValue::from_str(&self.key)
-                .expect("invalid user cb-access-key value"),
+            header_value(&self.key, "cb-access-key")?,
         );
         headers.insert(
             reqwest::header::HeaderName::from_static("cb-access-sign"),
-            reqwest::header::HeaderValue::from_str(&signature)
-                .expect("invalid cb-access-sign value"),
+            header_value(&signature, "cb-access-sign")?,
         );
         headers.insert(
             reqwest::header::HeaderName::from_static("cb-access-timestamp"),
-            reqwest::header::HeaderValue::from_str(&timestamp)
-                .expect("invalid user cb-access-timestamp value"),
+            header_value(&timestamp, "cb-access-timestamp")?,
         );
         headers.insert(
             reqwest::header::HeaderName::from_static("cb-access-passphrase"),
-            reqwest::header::HeaderValue::from_str(&self.passphrase)
-                .expect("invalid user cb-access-passphrase value"),
+            header_value(&self.passphrase, "cb-access-passphrase")?,
         );
 
-        headers
+        Ok(headers)
     }
 
-    fn sign_message(&self, url: &str, body: Option<&str>, timestamp: &str, method: &str) -> String {
+    fn sign_message(
+        &self,
+        url: &str,
+        body: Option<&str>,
+        timestamp: &str,
+        method: &str,
+    ) -> Result<String, Error> {
         let mut prehash = String::new();
         // omit body if not supplied
         match body {
             Some(body) => {
-                prehash.push_str(&timestamp);
-                prehash.push_str(&method);
-                prehash.push_str(&url);
-                prehash.push_str(&body);
+                prehash.push_str(timestamp);
+                prehash.push_str(method);
+                prehash.push_str(url);
+                prehash.push_str(body);
             }
             None => {
-                prehash.push_str(&timestamp);
-                prehash.push_str(&method);
-                prehash.push_str(&url);
+                prehash.push_str(timestamp);
+                prehash.push_str(method);
+                prehash.push_str(url);
             }
         }
         // decode your coinbase api secret
-        let decoded_secret = base64::decode(&self.secret)
-            .expect("unable to decode secret, is your secret in base 64 encoding");
+        let decoded_secret = base64::decode(&self.secret).map_err(|_| {
+            Error::auth("secret is not valid base64, is your secret in base 64 encoding")
+        })?;
         // hmac-sha256 it
         let mut hmac = crypto::hmac::Hmac::new(crypto::sha2::Sha256::new(), &decoded_secret);
         hmac.input(prehash.as_bytes());
@@ -166,44 +218,138 @@ impl PrivateClient {
         let hmac_code = hmac_result.code();
         let base64_encoding = base64::encode(hmac_code);
         // return base64 encoded hmac result
-        base64_encoding
+        Ok(base64_encoding)
+    }
+
+    /// Signs an authenticated websocket subscribe request with this client's credentials
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#subscribe)
+    pub fn websocket_auth(&self) -> Result<WebsocketAuth, Error> {
+        let timestamp = PrivateClient::get_current_timestamp()?;
+        Ok(WebsocketAuth {
+            signature: self.sign_message("/users/self/verify", None, &timestamp, "GET")?,
+            key: self.key.clone(),
+            passphrase: self.passphrase.clone(),
+            timestamp,
+        })
     }
 
     /// Creates a new `PrivateClient`
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// ~~~~
     pub fn new(secret: String, passphrase: String, key: String) -> Self {
-        Self {
-            reqwest_client: reqwest::Client::new(),
-            secret, // shared secret
-            key,
+        Self::with_transport(
+            secret,
             passphrase,
-            url: COINBASE_API_URL,
-        }
+            key,
+            ReqwestTransport::new(COINBASE_API_URL),
+        )
     }
 
     /// Creates a new `PrivateClient` for testing API connectivity and web trading
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// ~~~~
     pub fn new_sandbox(secret: String, passphrase: String, key: String) -> Self {
-        Self {
-            reqwest_client: reqwest::Client::new(),
+        Self::with_transport(
             secret,
+            passphrase,
+            key,
+            ReqwestTransport::new(COINBASE_SANDBOX_API_URL),
+        )
+    }
+
+    /// Creates a new `PrivateClient`, returning an `Auth` error if the credentials cannot sign requests
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::try_new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef")?;
+    /// ~~~~
+    pub fn try_new(secret: String, passphrase: String, key: String) -> Result<Self, Error> {
+        Self::try_with_transport(
+            secret,
+            passphrase,
+            key,
+            ReqwestTransport::new(COINBASE_API_URL),
+        )
+    }
+
+    /// Creates a new `PrivateClient` for the sandbox API, returning an `Auth` error if the credentials cannot sign requests
+    pub fn try_new_sandbox(secret: String, passphrase: String, key: String) -> Result<Self, Error> {
+        Self::try_with_transport(
+            secret,
+            passphrase,
+            key,
+            ReqwestTransport::new(COINBASE_SANDBOX_API_URL),
+        )
+    }
+
+    /// Creates a `PrivateClient` that sends its signed requests through `transport`, returning an `Auth` error if the credentials cannot sign requests
+    pub fn try_with_transport<T>(
+        secret: String,
+        passphrase: String,
+        key: String,
+        transport: T,
+    ) -> Result<Self, Error>
+    where
+        T: Transport + 'static,
+    {
+        Self::validate_credentials(&secret, &passphrase, &key)?;
+        Ok(Self::with_transport(secret, passphrase, key, transport))
+    }
+
+    /// Creates a `PrivateClient` that sends its signed requests through `transport`
+    pub fn with_transport<T>(secret: String, passphrase: String, key: String, transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
+        Self {
+            transport: Arc::new(transport),
+            rate_limiter: None,
+            retry_policy: None,
+            products: Arc::new(Mutex::new(HashMap::new())),
+            secret, // shared secret
             key,
             passphrase,
-            url: COINBASE_SANDBOX_API_URL,
         }
     }
 
+    /// Throttles the requests of this client and its clones with `rate_limiter`
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef")
+    ///     .with_rate_limiter(RateLimiter::private());
+    /// ~~~~
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Gets the `RateLimiter` of this client, if any
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Retries requests that fail with a transient error according to `retry_policy`
+    /// <br>
+    /// `place_order` is only retried for orders with a `client_oid`, see `RetryPolicy`
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef")
+    ///     .with_retry_policy(RetryPolicy::default());
+    /// ~~~~
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Gets a list of trading accounts from the profile of the API key.
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#account)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let accounts = client.get_accounts().await.unwrap();
     /// ~~~~
@@ -216,7 +362,7 @@ impl PrivateClient {
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#account)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let account = client.get_account("1f6a7175-a89c-494f-986d-af9987e6dd69")
     /// .await
@@ -231,7 +377,7 @@ impl PrivateClient {
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-account-history)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     ///  let history = client
     /// .get_account_history(
@@ -249,7 +395,7 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<AccountHistory>, Error> {
+    ) -> Result<Page<AccountHistory>, Error> {
         let account = self
             .get_paginated(
                 &format!("/accounts/{}/ledger?", account_id),
@@ -261,11 +407,43 @@ impl PrivateClient {
         Ok(account)
     }
 
+    /// Stream account activity of the API key's profile, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until an entry created before `until`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#get-account-history)
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let mut history = client.stream_account_history(
+    ///     "680f85f4-1a99-4108-93ce-a9066f9de246",
+    ///     Some(100),
+    ///     Some(Utc.ymd(2021, 6, 1).and_hms(0, 0, 0)),
+    /// );
+    /// while let Some(entry) = history.next().await {
+    ///     println!("{:?}", entry.unwrap());
+    /// }
+    /// ~~~~
+    pub fn stream_account_history<'a>(
+        &'a self,
+        account_id: &'a str,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<AccountHistory, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_account_history(account_id, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
+    }
+
     /// Get holds of an account that belong to the same profile as the API key.
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-holds)\
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let _holds = client
     /// .get_account_holds(
@@ -283,7 +461,7 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<Hold>, Error> {
+    ) -> Result<Page<Hold>, Error> {
         let account = self
             .get_paginated(
                 &format!("/accounts/{}/holds?", account_id),
@@ -295,63 +473,252 @@ impl PrivateClient {
         Ok(account)
     }
 
+    /// Stream holds of an account, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until a hold created before `until`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#get-holds)
+    pub fn stream_account_holds<'a>(
+        &'a self,
+        account_id: &'a str,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<Hold, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_account_holds(account_id, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
+    }
+
     /// You can place three types of orders: limit, market and stop
     /// <br>
+    /// Stop orders are created with `OrderBuilder::stop_limit`, sent as `stop` orders with a `stop_limit_price`, or with `OrderBuilder::stop_market`, sent as market orders with a stop price
+    /// <br>
     /// [Overview of order types and settings](https://help.coinbase.com/en/pro/trading-and-funding/orders/overview-of-order-types-and-settings-stop-limit-market)
     /// <br>
     /// Create order order using [`OrderBuilder`](https://docs.rs/coinbase-client/1.0.0-alpha/coinbase_client/private_client/struct.OrderBuilder.html)
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#place-a-new-order)
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
-    /// let order = OrderBuilder::market(OrderSide::Buy, "BTC-USD", SizeOrFunds::Funds(10.00))
+    /// let order = OrderBuilder::market(OrderSide::Buy, "BTC-USD", SizeOrFunds::funds("10.00"))
     /// .build();
     /// let res = client.place_order(order).await.unwrap();
     /// ~~~~
     pub async fn place_order(&self, order: Order) -> Result<String, Error> {
-        #[derive(Deserialize, Debug)]
-        pub struct OrderID {
-            pub id: String,
+        let (policy, client_oid) = match (&self.retry_policy, order.client_oid()) {
+            (Some(policy), Some(client_oid)) => (policy, client_oid.to_string()),
+            _ => {
+                return Ok(self
+                    .post_and_deserialize::<OrderID, _>("/orders", Some(order))
+                    .await?
+                    .id)
+            }
+        };
+        let body = serde_json::to_string(&order)?;
+        let mut retry = 0;
+        loop {
+            let result = self.send(Method::POST, "/orders", Some(body.clone())).await;
+            if retry >= policy.max_retries || !is_transient(&result) {
+                return Ok(deserialize_response::<OrderID>(Method::POST, "/orders", result?)?.id);
+            }
+            tokio::time::sleep(policy.delay(retry, result.as_ref().ok())).await;
+            retry += 1;
+            // the order may have been placed even though the response was lost
+            match self.get_order_by_oid(&client_oid).await {
+                Ok(order) => return Ok(order.id),
+                Err(err) if err.is_not_found() => {}
+                Err(err) => return Err(err),
+            }
         }
-        Ok(self
-            .post_and_deserialize::<OrderID, _>("/orders", Some(order))
-            .await?
-            .id)
+    }
+
+    /// Places `order` like `place_order` and returns both its server-assigned id and its client_oid
+    /// <br>
+    /// An order without a client_oid is assigned a random UUIDv4, which also lets a `RetryPolicy` retry it safely
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "33000.00", "1.0")
+    /// .build();
+    /// let placed = client.place_order_with_client_oid(order).await.unwrap();
+    /// registry.insert(&placed);
+    /// ~~~~
+    pub async fn place_order_with_client_oid(
+        &self,
+        mut order: Order,
+    ) -> Result<PlacedOrder, Error> {
+        let client_oid = order.ensure_client_oid().to_string();
+        Ok(PlacedOrder {
+            id: self.place_order(order).await?,
+            client_oid,
+        })
+    }
+
+    /// Validates `order` against the trading rules of its product with `Order::validate`, then places it
+    /// <br>
+    /// The product is fetched once and cached by this client and its clones for `PRODUCT_CACHE_TTL`, so that changes to its trading status are picked up
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "33000.00", "1.0")
+    /// .build();
+    /// let res = client.place_validated_order(order).await.unwrap();
+    /// ~~~~
+    pub async fn place_validated_order(&self, order: Order) -> Result<String, Error> {
+        let product = self.cached_product(order.product_id()).await?;
+        order.validate(&product)?;
+        self.place_order(order).await
+    }
+
+    async fn cached_product(&self, product_id: &str) -> Result<Arc<Product>, Error> {
+        if let Some((fetched, product)) = self.products.lock().unwrap().get(product_id) {
+            if fetched.elapsed() < PRODUCT_CACHE_TTL {
+                return Ok(product.clone());
+            }
+        }
+        let product: Arc<Product> = Arc::new(self.get(&format!("/products/{}", product_id)).await?);
+        self.products
+            .lock()
+            .unwrap()
+            .insert(product_id.to_string(), (Instant::now(), product.clone()));
+        Ok(product)
     }
 
     /// Cancel order specified by order ID
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#cancel-an-order)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
-    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", 33000.0, 1.0)
+    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "33000.00", "1.0")
     /// .build();
     /// let order_to_cancel_id = client.place_order(order).await.unwrap();
     /// let canceled_order_id = client.cancel_order(&order_to_cancel_id)
     /// .await.unwrap();
     /// ~~~~
     pub async fn cancel_order(&self, order_id: &str) -> Result<String, Error> {
-        Ok(self.delete(&format!("/orders/{}", order_id)).await?)
+        self.delete(&format!("/orders/{}", order_id)).await
     }
 
     /// Cancel order specified by order OID
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#cancel-an-order)
     pub async fn cancel_order_by_oid(&self, oid: &str) -> Result<String, Error> {
-        Ok(self.delete(&format!("/orders/client:{}", oid)).await?)
+        self.delete(&format!("/orders/client:{}", oid)).await
     }
 
     /// Cancel all orders
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#cancel-an-order)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let canceled_orders_ids = client.cancel_orders().await.unwrap();
     /// ~~~~
     pub async fn cancel_orders(&self) -> Result<Vec<String>, Error> {
-        Ok(self.delete("/orders").await?)
+        self.delete("/orders").await
+    }
+
+    /// Moves a resting limit order to `new_price` by canceling it and placing a replacement, as the API cannot amend orders
+    /// <br>
+    /// The cancel is confirmed by fetching the order before the replacement is placed. The replacement is a limit order on the same side with the same
+    /// time in force, post only flag and self-trade prevention and a generated client_oid, for `new_size` less the size filled before the cancel.
+    /// A good till time order is replaced with the `CancelAfter` it was placed with, so the replacement expires that long after it is placed.
+    /// Nothing is canceled if the order cannot be rebuilt that way, and nothing is placed if the order is not open, was filled meanwhile or the cancel
+    /// is still pending, see `ReplaceOutcome`
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// match client.replace_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2", "36010.00", "1.0").await.unwrap() {
+    ///     ReplaceOutcome::Replaced { replacement, .. } => println!("replaced by {}", replacement.id),
+    ///     outcome => println!("not replaced: {:?}", outcome),
+    /// }
+    /// ~~~~
+    pub async fn replace_order(
+        &self,
+        order_id: &str,
+        new_price: impl Into<Amount>,
+        new_size: impl Into<Amount>,
+    ) -> Result<ReplaceOutcome, Error> {
+        let (new_price, new_size) = (new_price.into(), new_size.into());
+        let size = parse_size("size", new_size.as_str())?;
+        let order = self.get_order(order_id).await?;
+        match (&order.status, &order.done_reason) {
+            (OrderState::Open, _) | (OrderState::Pending, _) => {}
+            (_, Some(DoneReason::Filled)) => return Ok(ReplaceOutcome::Filled(order)),
+            _ => return Ok(ReplaceOutcome::NotOpen(order)),
+        }
+        let options = match replacement_options(&order) {
+            Some(options) => options,
+            None => return Ok(ReplaceOutcome::NotReplaceable(order)),
+        };
+        // a 400 or 404 response means the order is done, its state tells whether it was filled
+        let cancel_error = match self.cancel_order(order_id).await {
+            Ok(_) => None,
+            Err(err) if err.is_not_found() || err.status() == Some(400) => Some(err),
+            Err(err) => return Err(err),
+        };
+        let canceled = match self.get_order(order_id).await {
+            Ok(canceled) => canceled,
+            // orders canceled without any fill are not kept
+            Err(err) if err.is_not_found() => match cancel_error {
+                None => {
+                    let replacement = self
+                        .place_replacement(&order, options, new_price, new_size.clone())
+                        .await?;
+                    return Ok(ReplaceOutcome::Replaced {
+                        canceled: None,
+                        replacement,
+                        size: new_size,
+                    });
+                }
+                Some(_) => return Ok(ReplaceOutcome::NotOpen(order)),
+            },
+            Err(err) => return Err(err),
+        };
+        match (&canceled.status, &canceled.done_reason, cancel_error) {
+            (_, Some(DoneReason::Filled), _) => Ok(ReplaceOutcome::Filled(canceled)),
+            (OrderState::Done, _, Some(_)) => Ok(ReplaceOutcome::NotOpen(canceled)),
+            (OrderState::Done, _, None) => {
+                let filled_size = parse_size("filled_size", &canceled.filled_size.to_string())?;
+                let remaining = size - filled_size;
+                if !remaining.is_positive() {
+                    return Ok(ReplaceOutcome::Canceled(canceled));
+                }
+                let remaining = Amount::from(remaining.to_string());
+                let replacement = self
+                    .place_replacement(&order, options, new_price, remaining.clone())
+                    .await?;
+                Ok(ReplaceOutcome::Replaced {
+                    canceled: Some(canceled),
+                    replacement,
+                    size: remaining,
+                })
+            }
+            (_, _, None) => Ok(ReplaceOutcome::CancelPending(canceled)),
+            (_, _, Some(err)) => Err(err),
+        }
+    }
+
+    async fn place_replacement(
+        &self,
+        order: &OrderInfo,
+        (time_in_force, self_trade_prevention): ReplacementOptions,
+        price: Amount,
+        size: Amount,
+    ) -> Result<PlacedOrder, Error> {
+        let builder = OrderBuilder::limit(order.side.clone(), &order.product_id, price, size)
+            .time_in_force(time_in_force);
+        let replacement = match self_trade_prevention {
+            Some(self_trade_prevention) => builder.self_trade_prevention(self_trade_prevention),
+            None => builder,
+        }
+        .build();
+        self.place_order_with_client_oid(replacement).await
     }
 
     /// Get open orders from the profile that the API key belongs
@@ -360,7 +727,7 @@ impl PrivateClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let orders = client
     ///     .get_orders(
@@ -378,7 +745,7 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<OrderInfo>, Error> {
+    ) -> Result<Page<OrderInfo>, Error> {
         let path = match order_status {
             Some(n) => {
                 let params = match n {
@@ -395,36 +762,56 @@ impl PrivateClient {
             None => String::from("/orders?"),
         };
 
-        Ok(self.get_paginated(&path, before, after, limit).await?)
+        self.get_paginated(&path, before, after, limit).await
+    }
+
+    /// Stream orders from the profile that the API key belongs, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until an order created before `until`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#list-orders)
+    pub fn stream_orders(
+        &self,
+        order_status: Option<OrderStatus>,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'_, Result<OrderInfo, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_orders(order_status, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
     }
 
     /// Get open order from the profile that the API key belongs
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-an-order)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
-    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", 36000.0, 1.0)
+    /// let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.00", "1.0")
     /// .build();
     /// let order_id = client.place_order(order).await.unwrap();
     /// let order = client.get_order(&order_id).await.unwrap();
     /// ~~~~
     pub async fn get_order(&self, order_id: &str) -> Result<OrderInfo, Error> {
-        Ok(self.get(&format!("/orders/{}", order_id)).await?)
+        self.get(&format!("/orders/{}", order_id)).await
     }
 
     /// Gets order specified by order OID
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-an-order)
     pub async fn get_order_by_oid(&self, oid: &str) -> Result<OrderInfo, Error> {
-        Ok(self.get(&format!("/orders/client:{}", oid)).await?)
+        self.get(&format!("/orders/client:{}", oid)).await
     }
 
     /// Get recent fills by specified order_id of the API key's profile
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#fills)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let fills = client
     ///     .get_fill_by_order_id("4f2756cf-dcb5-492b-83e5-5f2141892758", None, None, None)
@@ -437,22 +824,41 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<Fill>, Error> {
-        Ok(self
-            .get_paginated(
-                &format!("/fills?order_id={}&", order_id),
-                before,
-                after,
-                limit,
-            )
-            .await?)
+    ) -> Result<Page<Fill>, Error> {
+        self.get_paginated(
+            &format!("/fills?order_id={}&", order_id),
+            before,
+            after,
+            limit,
+        )
+        .await
+    }
+
+    /// Stream fills of the specified order_id, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until a fill created before `until`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#fills)
+    pub fn stream_fill_by_order_id<'a>(
+        &'a self,
+        order_id: &'a str,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<Fill, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_fill_by_order_id(order_id, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
     }
 
     /// Get recent fills by specified product_id of the API key's profile
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#fills)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let fills = client
     ///     .get_fills_by_product_id(&product_id, None, Some("29786034"), None)
@@ -465,27 +871,55 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<Fill>, Error> {
-        Ok(self
-            .get_paginated(
-                &format!("/fills?product_id={}&", product_id),
-                before,
-                after,
-                limit,
-            )
-            .await?)
+    ) -> Result<Page<Fill>, Error> {
+        self.get_paginated(
+            &format!("/fills?product_id={}&", product_id),
+            before,
+            after,
+            limit,
+        )
+        .await
+    }
+
+    /// Stream fills of the specified product_id, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until a fill created before `until`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#fills)
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let fills: Vec<Fill> = client
+    ///     .stream_fills_by_product_id("BTC-USD", None, None)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// ~~~~
+    pub fn stream_fills_by_product_id<'a>(
+        &'a self,
+        product_id: &'a str,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<Fill, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_fills_by_product_id(product_id, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
     }
 
     /// Get information on your payment method transfer limits, as well as buy/sell limits per currency
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#limits)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let limits = client.get_limits().await.unwrap();
     /// ~~~~
-    pub async fn get_limits(&self) -> Result<Json, Error> {
-        Ok(self.get(&format!("/users/self/exchange-limits")).await?)
+    pub async fn get_limits(&self) -> Result<ExchangeLimits, Error> {
+        self.get("/users/self/exchange-limits").await
     }
 
     /// Get deposits from the profile of the API key, in descending order by created time
@@ -504,7 +938,7 @@ impl PrivateClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let deposits = client
     ///     .get_deposits(
@@ -521,12 +955,12 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Json, Error> {
+    ) -> Result<Page<Transfer>, Error> {
         let path = match profile_id {
             Some(n) => format!("/transfers?type=deposit&profile_id={}&", n),
             None => String::from("/transfers?type=deposit&"),
         };
-        Ok(self.get_paginated(&path, before, after, limit).await?)
+        self.get_paginated(&path, before, after, limit).await
     }
     /// Get internal deposits from the profile of the API key, in descending order by created time
     /// <br>
@@ -544,7 +978,7 @@ impl PrivateClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let deposits = client
     /// .get_internal_deposits(
@@ -561,115 +995,113 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Json, Error> {
+    ) -> Result<Page<Transfer>, Error> {
         let path = match profile_id {
             Some(n) => format!("/transfers?type=internal_deposit&profile_id={}&", n),
             None => String::from("/transfers?type=internal_deposit&"),
         };
-        Ok(self.get_paginated(&path, before, after, limit).await?)
+        self.get_paginated(&path, before, after, limit).await
     }
 
     /// Get information on a single deposit
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#single-deposit)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let deposit = client
     /// .get_deposit("80259339-7bf9-498f-8200-ddbd32a1c545")
     /// .await;
     /// ~~~~
-    pub async fn get_deposit(&self, transfer_id: &str) -> Result<Json, Error> {
-        Ok(self.get(&format!("/transfers/{}", transfer_id)).await?)
+    pub async fn get_deposit(&self, transfer_id: &str) -> Result<Transfer, Error> {
+        self.get(&format!("/transfers/{}", transfer_id)).await
     }
 
     /// Get your payment methods
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#payment-methods)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let payment_methods = client.get_payment_methods().await.unwrap();
     /// ~~~~
-    pub async fn get_payment_methods(&self) -> Result<Json, Error> {
-        Ok(self.get("/payment-methods").await?)
+    pub async fn get_payment_methods(&self) -> Result<Vec<PaymentMethod>, Error> {
+        self.get("/payment-methods").await
     }
 
     /// Deposit funds from a payment method
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#payment-method)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let res = client
-    /// .deposit_funds(10.00, "USD", "1b4b4fbc-8921-5e7c-b362-a1c589a2cf20")
+    /// .deposit_funds("10.00", "USD", "1b4b4fbc-8921-5e7c-b362-a1c589a2cf20")
     /// .await
     /// .unwrap();
     /// ~~~~
     pub async fn deposit_funds(
         &self,
-        amount: &str,
+        amount: impl Into<Amount>,
         currency: &str,
         payment_method_id: &str,
     ) -> Result<DepositInfo, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/deposits/payment-method",
-                Some(serde_json::json!({
-                        "amount": amount,
-                        "currency": currency,
-                        "payment_method_id": payment_method_id
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/deposits/payment-method",
+            Some(serde_json::json!({
+                    "amount": amount.into(),
+                    "currency": currency,
+                    "payment_method_id": payment_method_id
+            })),
+        )
+        .await
     }
 
     /// Deposit funds from a coinbase account
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#coinbase)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let res = client
-    ///     .deposit_funds_from_coinbase(10.00, "BTC", "95671473-4dda-5264-a654-fc6923e8a334")
+    ///     .deposit_funds_from_coinbase("10.00", "BTC", "95671473-4dda-5264-a654-fc6923e8a334")
     ///     .await
     ///     .unwrap();
     /// ~~~~
     pub async fn deposit_funds_from_coinbase(
         &self,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: &str,
         coinbase_account_id: &str,
     ) -> Result<DepositInfo, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/deposits/coinbase-account",
-                Some(serde_json::json!({
-                        "amount": amount,
-                        "currency": currency,
-                        "coinbase_account_id": coinbase_account_id
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/deposits/coinbase-account",
+            Some(serde_json::json!({
+                    "amount": amount.into(),
+                    "currency": currency,
+                    "coinbase_account_id": coinbase_account_id
+            })),
+        )
+        .await
     }
 
     /// Get a list of your coinbase accounts
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#coinbase-accounts)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let accounts = client.get_coinbase_accounts().await.unwrap();
     /// ~~~~
-    pub async fn get_coinbase_accounts(&self) -> Result<Json, Error> {
-        Ok(self.get("/coinbase-accounts").await?)
+    pub async fn get_coinbase_accounts(&self) -> Result<Vec<CoinbaseAccount>, Error> {
+        self.get("/coinbase-accounts").await
     }
 
     /// Generate an address for crypto deposits
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#generate-a-crypto-deposit-address)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let address = client
     ///     .generate_crypto_deposit_address("95671473-4dda-5264-a654-fc6923e8a334")
@@ -679,13 +1111,12 @@ impl PrivateClient {
     pub async fn generate_crypto_deposit_address(
         &self,
         coinbase_account_id: &str,
-    ) -> Result<Json, Error> {
-        Ok(self
-            .post_and_deserialize::<_, Json>(
-                &format!("/coinbase-accounts/{}/addresses", coinbase_account_id),
-                None,
-            )
-            .await?)
+    ) -> Result<CryptoAddress, Error> {
+        self.post_and_deserialize::<_, Json>(
+            &format!("/coinbase-accounts/{}/addresses", coinbase_account_id),
+            None,
+        )
+        .await
     }
 
     /// Get withdrawals from the profile of the API key
@@ -704,7 +1135,7 @@ impl PrivateClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let withdrawals = client
     ///     .get_withdrawals(
@@ -722,12 +1153,12 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Json, Error> {
+    ) -> Result<Page<Transfer>, Error> {
         let path = match profile_id {
             Some(n) => format!("/transfers?type=withdraw&profile_id={}&", n),
             None => String::from("/transfers?type=withdraw&"),
         };
-        Ok(self.get_paginated(&path, before, after, limit).await?)
+        self.get_paginated(&path, before, after, limit).await
     }
 
     /// Get withdrawals from the profile of the API key
@@ -749,7 +1180,7 @@ impl PrivateClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let withdrawals = client
     ///     .get_internal_withdrawals(
@@ -767,27 +1198,27 @@ impl PrivateClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Json, Error> {
+    ) -> Result<Page<Transfer>, Error> {
         let path = match profile_id {
             Some(n) => format!("/transfers?type=internal_withdraw&profile_id={}&", n),
             None => String::from("/transfers?type=internal_withdraw&"),
         };
-        Ok(self.get_paginated(&path, before, after, limit).await?)
+        self.get_paginated(&path, before, after, limit).await
     }
 
     /// Get information on a single withdrawal
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#single-withdrawal)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let withdrawal = client
     ///     .get_withdrawal("0e94a87f-9d50-4ead-86ac-7898830c5edf")
     ///     .await
     ///     .unwrap();
     /// ~~~~
-    pub async fn get_withdrawal(&self, transfer_id: &str) -> Result<Json, Error> {
-        Ok(self.get(&format!("/transfers/{}", transfer_id)).await?)
+    pub async fn get_withdrawal(&self, transfer_id: &str) -> Result<Transfer, Error> {
+        self.get(&format!("/transfers/{}", transfer_id)).await
     }
 
     /// Withdraw funds to a payment method
@@ -795,49 +1226,47 @@ impl PrivateClient {
     /// [API docs](https://docs.pro.coinbase.com/#payment-method55)
     pub async fn withdraw_funds(
         &self,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: &str,
         payment_method_id: &str,
     ) -> Result<WithdrawInfo, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/withdrawals/payment-method",
-                Some(serde_json::json!({
-                        "amount": amount,
-                        "currency": currency,
-                        "payment_method_id": payment_method_id
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/withdrawals/payment-method",
+            Some(serde_json::json!({
+                    "amount": amount.into(),
+                    "currency": currency,
+                    "payment_method_id": payment_method_id
+            })),
+        )
+        .await
     }
 
     /// Withdraw funds to a coinbase account
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#coinbase56)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let res = client
-    ///     .withdraw_to_coinbase(1.0, "ADA", "91bdfea7-f2sd-5waa-bb0d-5b93c9f09ffc")
+    ///     .withdraw_to_coinbase("1.0", "ADA", "91bdfea7-f2sd-5waa-bb0d-5b93c9f09ffc")
     ///     .await
     ///     .unwrap();    
     /// ~~~~
     pub async fn withdraw_to_coinbase(
         &self,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: &str,
         coinbase_account_id: &str,
     ) -> Result<WithdrawInfo, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/withdrawals/coinbase-account",
-                Some(serde_json::json!({
-                        "amount": amount,
-                        "currency": currency,
-                        "coinbase_account_id": coinbase_account_id
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/withdrawals/coinbase-account",
+            Some(serde_json::json!({
+                    "amount": amount.into(),
+                    "currency": currency,
+                    "coinbase_account_id": coinbase_account_id
+            })),
+        )
+        .await
     }
 
     /// Withdraw funds to a crypto address.
@@ -852,57 +1281,56 @@ impl PrivateClient {
     /// <br>
     /// destination_tag: A destination tag for currencies that support one
     /// <br>
-    /// no_destination_tag:	A boolean flag to opt out of using a destination tag for currencies that support one. This is required when not providing a destination tag.
+    /// no_destination_tag: A boolean flag to opt out of using a destination tag for currencies that support one. This is required when not providing a destination tag.
     /// <br>
     /// add_network_fee_to_total: A boolean flag to add the network fee on top of the amount. If this is blank, it will default to deducting the network fee from the amount.
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#crypto)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
-    /// let res = client.withdraw_to_crypto_address(6.0, "ADA", "addr1qyk0yr3ht9d6hcqwp8q8j38nxs04npyjauzz9wp5jcfr95h64lvegfk57zmzltj3nmpjff6490ayyvjh0g6sne6hm3hspnnscy", None, None, None).await.unwrap();
+    /// let res = client.withdraw_to_crypto_address("6.0", "ADA", "addr1qyk0yr3ht9d6hcqwp8q8j38nxs04npyjauzz9wp5jcfr95h64lvegfk57zmzltj3nmpjff6490ayyvjh0g6sne6hm3hspnnscy", None, None, None).await.unwrap();
     /// ~~~~
     pub async fn withdraw_to_crypto_address(
         &self,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: &str,
         crypto_address: &str,
         destination_tag: Option<&str>,
         no_destination_tag: Option<bool>,
         add_network_fee_to_total: Option<bool>,
     ) -> Result<Json, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/withdrawals/crypto",
-                Some(serde_json::json!({
-                        "amount": amount,
-                        "currency": currency,
-                        "crypto_address": crypto_address,
-                        "destination_tag": destination_tag,
-                        "no_destination_tag": no_destination_tag,
-                        "add_network_fee_to_total": add_network_fee_to_total
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/withdrawals/crypto",
+            Some(serde_json::json!({
+                    "amount": amount.into(),
+                    "currency": currency,
+                    "crypto_address": crypto_address,
+                    "destination_tag": destination_tag,
+                    "no_destination_tag": no_destination_tag,
+                    "add_network_fee_to_total": add_network_fee_to_total
+            })),
+        )
+        .await
     }
 
     /// Get your current maker & taker fee rates, as well as your 30-day trailing volume
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-current-fees)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let fees = client.get_fees().await.unwrap();
     /// ~~~~
     pub async fn get_fees(&self) -> Result<Fees, Error> {
-        Ok(self.get("/fees").await?)
+        self.get("/fees").await
     }
 
     /// Get the network fee estimate when sending to the given address
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#fee-estimate)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let fee = client
     ///     .get_fee_estimate("ETH", "0x82289D45Ee8E806C63Ba0DC94a22d4238525d815")
@@ -931,10 +1359,10 @@ impl PrivateClient {
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#stablecoin-conversions)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let conversion = client
-    ///     .convert_stablecoin("USD", "USDC", 10.00)
+    ///     .convert_stablecoin("USD", "USDC", "10.00")
     ///     .await
     ///     .unwrap();
     /// ~~~~
@@ -942,18 +1370,17 @@ impl PrivateClient {
         &self,
         from_currency_id: &str,
         to_currency_id: &str,
-        amount: f64,
+        amount: impl Into<Amount>,
     ) -> Result<StablecoinConversion, Error> {
-        Ok(self
-            .post_and_deserialize(
-                "/conversions",
-                Some(serde_json::json!({
-                    "from": from_currency_id,
-                    "to": to_currency_id,
-                    "amount": amount
-                })),
-            )
-            .await?)
+        self.post_and_deserialize(
+            "/conversions",
+            Some(serde_json::json!({
+                "from": from_currency_id,
+                "to": to_currency_id,
+                "amount": amount.into()
+            })),
+        )
+        .await
     }
 
     /// Reports provide batches of historic information about your profile in various human and machine readable forms    
@@ -962,7 +1389,7 @@ impl PrivateClient {
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#create-a-new-report)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let report = Report::account_builder(
     ///     "2014-11-01T00:00:00.000Z",
@@ -974,8 +1401,8 @@ impl PrivateClient {
     /// .build();
     /// let res = client.create_report(report).await.unwrap();
     /// ~~~~
-    pub async fn create_report<'a>(&self, report: Report) -> Result<ReportInfo, Error> {
-        Ok(self.post_and_deserialize("/reports", Some(report)).await?)
+    pub async fn create_report(&self, report: Report) -> Result<ReportInfo, Error> {
+        self.post_and_deserialize("/reports", Some(report)).await
     }
 
     /// Get report status
@@ -984,7 +1411,7 @@ impl PrivateClient {
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-report-status)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let report = client
     /// .get_report("d4a3e847-b618-454d-bcb3-e77b0ad61600")
@@ -992,26 +1419,26 @@ impl PrivateClient {
     /// .unwrap();
     /// ~~~~
     pub async fn get_report(&self, report_id: &str) -> Result<ReportInfo, Error> {
-        Ok(self.get(&format!("/reports/{}", report_id)).await?)
+        self.get(&format!("/reports/{}", report_id)).await
     }
 
     /// Get your profiles
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#list-profiles)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let profiles = client.get_profiles().await.unwrap();
     /// ~~~~
     pub async fn get_profiles(&self) -> Result<Vec<Profile>, Error> {
-        Ok(self.get("/profiles").await?)
+        self.get("/profiles").await
     }
 
     /// Get a single profile by profile id
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#get-a-profile)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let profile = client
     /// .get_profile("e1d7731f-b7e2-4285-b711-eeec76fc2aff")
@@ -1019,21 +1446,21 @@ impl PrivateClient {
     /// .unwrap();
     /// ~~~~
     pub async fn get_profile(&self, profile_id: &str) -> Result<Profile, Error> {
-        Ok(self.get(&format!("/profiles/{}", profile_id)).await?)
+        self.get(&format!("/profiles/{}", profile_id)).await
     }
 
     /// Transfer funds from API key's profile to another user owned profile
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#create-profile-transfer)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
     /// let ok = client
     ///     .create_profile_transfer(
     ///         "e1d7731f-b7e2-4285-b711-eeec76fc2aff",
     ///         "3510ac37-1a99-4c9c-9865-15f1bc5a832e",
     ///         "USD",
-    ///         100.00,
+    ///         "100.00",
     ///     )
     ///     .await
     ///     .unwrap();
@@ -1043,7 +1470,7 @@ impl PrivateClient {
         from: &str,
         to: &str,
         currency: &str,
-        amount: f64,
+        amount: impl Into<Amount>,
     ) -> Result<String, Error> {
         let response = self
             .post(
@@ -1053,31 +1480,34 @@ impl PrivateClient {
                         "from": from,
                         "to": to,
                         "currency": currency,
-                        "amount": amount
+                        "amount": amount.into()
                     }
                 )),
             )
             .await?;
-        let status = response.status();
-        if !status.is_success() {
-            let error_message = response.json::<ErrorMessage>().await?;
-            return Err(Error::new(ErrorKind::Status(StatusError::new(
-                status.as_u16(),
-                error_message.message,
-            ))));
+        if !response.is_success() {
+            return Err(status_error(Method::POST, "/profiles/transfer", &response));
         }
-        Ok(response.text().await?)
+        Ok(response.body)
     }
 
     /// Get cryptographically signed prices ready to be posted on-chain using Open Oracle smart contracts.
     /// <br>
     /// [API docs](https://docs.pro.coinbase.com/#oracle)
-    pub async fn oracle(&self) -> Result<Json, Error> {
-        Ok(self.get("/oracle").await?)
+    /// <br>
+    /// With the `oracle` feature, `OracleData::verify` checks that the prices are signed by Coinbase
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let prices = client.oracle().await.unwrap().verify().unwrap();
+    /// ~~~~
+    pub async fn oracle(&self) -> Result<OracleData, Error> {
+        self.get("/oracle").await
     }
 }
 
 /// Limit list of orders to these statuses. Passing `OpenActivePending` returns orders of all statuses.
+#[derive(Clone, Copy, Debug)]
 pub enum OrderStatus {
     Open,
     Active,
@@ -1119,10 +1549,26 @@ pub struct AccountHistory {
     pub created_at: DateTime<Utc>,
     pub amount: String,
     pub balance: String,
-    pub r#type: String,
+    pub r#type: LedgerEntryType,
     pub details: AccountHistoryDetails,
 }
 
+string_enum! {
+    /// The type of an `AccountHistory` entry
+    pub enum LedgerEntryType {
+        /// Funds moved to or from Coinbase
+        Transfer => "transfer",
+        /// Funds moved as a result of a trade
+        Match => "match",
+        /// Fee as a result of a trade
+        Fee => "fee",
+        /// Fee rebate as per the fee schedule
+        Rebate => "rebate",
+        /// Funds converted between fiat currency and a stablecoin
+        Conversion => "conversion",
+    }
+}
+
 /// A structure that represents an Account Hold
 #[derive(Deserialize, Debug)]
 pub struct Hold {
@@ -1137,6 +1583,18 @@ pub struct Hold {
     pub r#ref: String,
 }
 
+impl CreatedAt for AccountHistory {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl CreatedAt for Hold {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
 /// A structure that represents Account History Details
 #[derive(Deserialize, Debug)]
 pub struct AccountHistoryDetails {
@@ -1162,25 +1620,227 @@ pub struct WithdrawInfo {
     pub currency: String,
 }
 
+/// A structure that represents a deposit or withdrawal
+/// <br>
+/// The API has no status field, `status` derives it from `canceled_at`, `completed_at` and `processed_at`
+#[derive(Deserialize, Debug)]
+pub struct Transfer {
+    pub id: String,
+    pub r#type: TransferType,
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub canceled_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub processed_at: Option<DateTime<Utc>>,
+    pub account_id: Option<String>,
+    pub user_id: Option<String>,
+    pub user_nonce: Option<String>,
+    pub amount: String,
+    #[serde(default)]
+    pub details: TransferDetails,
+}
+
+impl Transfer {
+    /// The status of the transfer
+    pub fn status(&self) -> TransferStatus {
+        if self.canceled_at.is_some() {
+            TransferStatus::Canceled
+        } else if self.completed_at.is_some() {
+            TransferStatus::Completed
+        } else if self.processed_at.is_some() {
+            TransferStatus::Processed
+        } else {
+            TransferStatus::Pending
+        }
+    }
+}
+
+impl CreatedAt for Transfer {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A structure that represents the details of a `Transfer`
+/// <br>
+/// Crypto transfers have the address, transaction hash and destination tag, transfers from or to Coinbase or a payment method have the id of the account or payment method
+#[derive(Deserialize, Debug, Default)]
+pub struct TransferDetails {
+    pub crypto_address: Option<String>,
+    pub crypto_transaction_hash: Option<String>,
+    pub crypto_transaction_id: Option<String>,
+    pub destination_tag: Option<String>,
+    pub destination_tag_name: Option<String>,
+    pub sent_to_address: Option<String>,
+    pub coinbase_account_id: Option<String>,
+    pub coinbase_transaction_id: Option<String>,
+    pub coinbase_withdrawal_id: Option<String>,
+    pub coinbase_payment_method_id: Option<String>,
+}
+
+string_enum! {
+    /// The type of a `Transfer`
+    pub enum TransferType {
+        Deposit => "deposit",
+        Withdraw => "withdraw",
+        InternalDeposit => "internal_deposit",
+        InternalWithdraw => "internal_withdraw",
+    }
+}
+
+/// The status of a `Transfer`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// Created but not processed yet
+    Pending,
+    /// Processed but not completed yet
+    Processed,
+    Completed,
+    Canceled,
+}
+
 /// A structure that represents Order Info
+/// <br>
+/// Fields that only apply to some orders are `None` when the API omits them: `price` for market orders,
+/// `size` for market orders placed with funds, `funds` and `specified_funds` for market orders placed with funds,
+/// `stop` and `stop_price` for stop orders, `expire_time` for good till time orders and `done_at` and `done_reason`
+/// until the order is done
 #[derive(Debug, Deserialize)]
 pub struct OrderInfo {
     pub id: String,
-    pub price: String,
-    pub size: String,
+    pub price: Option<String>,
+    pub size: Option<String>,
     pub product_id: String,
-    pub side: String,
+    pub profile_id: Option<String>,
+    pub side: OrderSide,
+    pub funds: Option<String>,
+    pub specified_funds: Option<String>,
     pub stp: Option<String>,
-    pub r#type: String,
-    pub time_in_force: String,
+    pub r#type: OrderType,
+    pub time_in_force: Option<OrderTimeInForce>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub expire_time: Option<DateTime<Utc>>,
+    #[serde(default)]
     pub post_only: bool,
     #[serde(deserialize_with = "deserialize_to_date")]
     pub created_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub done_at: Option<DateTime<Utc>>,
+    pub done_reason: Option<DoneReason>,
+    pub reject_reason: Option<String>,
     pub fill_fees: String,
     pub filled_size: String,
-    pub executed_value: String,
-    pub status: String,
+    pub executed_value: Option<String>,
+    pub status: OrderState,
     pub settled: bool,
+    pub stop: Option<OrderStop>,
+    pub stop_price: Option<String>,
+    pub client_oid: Option<String>,
+}
+
+impl CreatedAt for OrderInfo {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+// converts a credential or signature into the value of the header `name`
+fn header_value(value: &str, name: &str) -> Result<reqwest::header::HeaderValue, Error> {
+    reqwest::header::HeaderValue::from_str(value)
+        .map_err(|_| Error::auth(&format!("invalid {} header value", name)))
+}
+
+/// A structure that represents the ids of an order placed with `PrivateClient::place_order_with_client_oid`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlacedOrder {
+    pub id: String,
+    pub client_oid: String,
+}
+
+/// The result of `PrivateClient::replace_order`
+#[derive(Debug)]
+pub enum ReplaceOutcome {
+    /// The order was canceled and `replacement` was placed for `size`, the new size less the size filled before the cancel
+    /// <br>
+    /// `canceled` is the final state of the canceled order, `None` if it had no fills as such orders are not kept
+    Replaced {
+        canceled: Option<OrderInfo>,
+        replacement: PlacedOrder,
+        size: Amount,
+    },
+    /// The order was canceled, but the size filled before the cancel is at least the new size so no replacement was placed
+    Canceled(OrderInfo),
+    /// The order was filled before it could be canceled, no replacement was placed
+    Filled(OrderInfo),
+    /// The order was not open, e.g. an untriggered stop order or an order canceled elsewhere, no replacement was placed
+    NotOpen(OrderInfo),
+    /// The cancel was accepted but the order is not done yet, no replacement was placed
+    /// <br>
+    /// Call `replace_order` again to confirm the cancel and place the replacement
+    CancelPending(OrderInfo),
+    /// The side, time in force, expire time or self-trade prevention of the order cannot be carried over to a replacement, the order was not canceled
+    NotReplaceable(OrderInfo),
+}
+
+// the time in force and self-trade prevention a replacement of an order is placed with
+type ReplacementOptions = (TimeInForce, Option<SelfTradePrevention>);
+
+// the options of `order` for its replacement, `None` if they cannot be rebuilt from it
+fn replacement_options(order: &OrderInfo) -> Option<ReplacementOptions> {
+    if let OrderSide::Unknown(_) = order.side {
+        return None;
+    }
+    let post_only = order.post_only;
+    let time_in_force = match &order.time_in_force {
+        None | Some(OrderTimeInForce::GoodTillCancel) => TimeInForce::GoodTillCancel { post_only },
+        Some(OrderTimeInForce::GoodTillTime) => TimeInForce::GoodTillTime {
+            cancel_after: cancel_after(order.created_at, order.expire_time?)?,
+            post_only,
+        },
+        Some(OrderTimeInForce::ImmediateOrCancel) => TimeInForce::ImmediateOrCancel,
+        Some(OrderTimeInForce::FillOrKill) => TimeInForce::FillOrKill,
+        Some(OrderTimeInForce::Unknown(_)) => return None,
+    };
+    let self_trade_prevention = match order.stp.as_deref() {
+        None => None,
+        Some("dc") => Some(SelfTradePrevention::DecreaseCancel),
+        Some("co") => Some(SelfTradePrevention::CancelOldest),
+        Some("cn") => Some(SelfTradePrevention::CancelNewest),
+        Some("cb") => Some(SelfTradePrevention::CancelBoth),
+        Some(_) => return None,
+    };
+    Some((time_in_force, self_trade_prevention))
+}
+
+// the `CancelAfter` of a good till time order placed at `created_at` that expires at `expire_time`
+fn cancel_after(created_at: DateTime<Utc>, expire_time: DateTime<Utc>) -> Option<CancelAfter> {
+    let lifetime = expire_time - created_at;
+    [
+        (CancelAfter::Minute, chrono::Duration::minutes(1)),
+        (CancelAfter::Hour, chrono::Duration::hours(1)),
+        (CancelAfter::Day, chrono::Duration::days(1)),
+    ]
+    .iter()
+    .find(|(_, duration)| (lifetime - *duration).num_milliseconds().abs() < 1000)
+    .map(|(cancel_after, _)| *cancel_after)
+}
+
+// parses a size for `replace_order`, which subtracts the filled size without losing precision
+fn parse_size(field: &'static str, value: &str) -> Result<FixedDecimal, Error> {
+    FixedDecimal::parse(value).ok_or_else(|| {
+        Error::new(ErrorKind::Validation(ValidationError::InvalidNumber {
+            field,
+            value: value.to_string(),
+        }))
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct OrderID {
+    id: String,
 }
 
 /// A structure that represents Report Info
@@ -1218,10 +1878,24 @@ pub struct Fill {
     pub order_id: String,
     #[serde(deserialize_with = "deserialize_to_date")]
     pub created_at: DateTime<Utc>,
-    pub liquidity: String,
+    pub liquidity: Liquidity,
     pub fee: String,
     pub settled: bool,
-    pub side: String,
+    pub side: OrderSide,
+}
+
+string_enum! {
+    /// Whether a `Fill` was the maker or the taker of the trade
+    pub enum Liquidity {
+        Maker => "M",
+        Taker => "T",
+    }
+}
+
+impl CreatedAt for Fill {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
 }
 
 /// A structure that represents your current maker & taker fee rates, as well as your 30-day trailing volume
@@ -1232,6 +1906,170 @@ pub struct Fees {
     pub usd_volume: Option<String>,
 }
 
+/// A structure that represents your exchange limits
+/// <br>
+/// `transfer_limits` maps each transfer type, like `ach`, `buy` or `exchange_withdraw`, to the limit of each currency in `limit_currency`
+#[derive(Debug, Deserialize)]
+pub struct ExchangeLimits {
+    pub limit_currency: String,
+    pub transfer_limits: HashMap<String, HashMap<String, TransferLimit>>,
+}
+
+/// A structure that represents the limit of a transfer type for a currency
+#[derive(Debug, Deserialize)]
+pub struct TransferLimit {
+    pub max: f64,
+    pub remaining: f64,
+    pub period_in_days: Option<u32>,
+}
+
+/// A structure that represents a Payment Method
+/// <br>
+/// `limits` has the limits of each kind of transfer the payment method allows
+#[derive(Debug, Deserialize)]
+pub struct PaymentMethod {
+    pub id: String,
+    pub r#type: PaymentMethodType,
+    pub name: String,
+    pub currency: String,
+    pub primary_buy: bool,
+    pub primary_sell: bool,
+    pub allow_buy: bool,
+    pub allow_sell: bool,
+    pub allow_deposit: bool,
+    pub allow_withdraw: bool,
+    #[serde(default)]
+    pub limits: PaymentMethodLimits,
+}
+
+string_enum! {
+    /// The type of a `PaymentMethod`
+    pub enum PaymentMethodType {
+        AchBankAccount => "ach_bank_account",
+        BankWire => "bank_wire",
+        SepaBankAccount => "sepa_bank_account",
+        FiatAccount => "fiat_account",
+        CreditCard => "credit_card",
+        PaypalAccount => "paypal_account",
+    }
+}
+
+/// A structure that represents the limits of a `PaymentMethod`
+#[derive(Debug, Default, Deserialize)]
+pub struct PaymentMethodLimits {
+    #[serde(default)]
+    pub buy: Vec<PaymentMethodLimit>,
+    #[serde(default)]
+    pub instant_buy: Vec<PaymentMethodLimit>,
+    #[serde(default)]
+    pub sell: Vec<PaymentMethodLimit>,
+    #[serde(default)]
+    pub deposit: Vec<PaymentMethodLimit>,
+}
+
+/// A structure that represents the limit of a `PaymentMethod` over a period of days
+#[derive(Debug, Deserialize)]
+pub struct PaymentMethodLimit {
+    pub period_in_days: u32,
+    pub total: LimitAmount,
+    pub remaining: LimitAmount,
+}
+
+/// A structure that represents an amount of a limit
+#[derive(Debug, Deserialize)]
+pub struct LimitAmount {
+    pub amount: String,
+    pub currency: String,
+}
+
+/// A structure that represents a Coinbase Account
+/// <br>
+/// Fiat accounts have the information to deposit to them by wire or SEPA transfer
+#[derive(Debug, Deserialize)]
+pub struct CoinbaseAccount {
+    pub id: String,
+    pub name: String,
+    pub balance: String,
+    pub currency: String,
+    pub r#type: CoinbaseAccountType,
+    pub primary: bool,
+    pub active: bool,
+    pub wire_deposit_information: Option<WireDepositInformation>,
+    pub sepa_deposit_information: Option<SepaDepositInformation>,
+}
+
+string_enum! {
+    /// The type of a `CoinbaseAccount`
+    pub enum CoinbaseAccountType {
+        Wallet => "wallet",
+        Fiat => "fiat",
+        Vault => "vault",
+    }
+}
+
+/// A structure that represents the information to deposit to a `CoinbaseAccount` by wire transfer
+#[derive(Debug, Deserialize)]
+pub struct WireDepositInformation {
+    pub account_number: Option<String>,
+    pub routing_number: String,
+    pub bank_name: String,
+    pub bank_address: String,
+    pub bank_country: BankCountry,
+    pub account_name: String,
+    pub account_address: String,
+    pub reference: String,
+}
+
+/// A structure that represents the country of a bank
+#[derive(Debug, Deserialize)]
+pub struct BankCountry {
+    pub code: String,
+    pub name: String,
+}
+
+/// A structure that represents the information to deposit to a `CoinbaseAccount` by SEPA transfer
+#[derive(Debug, Deserialize)]
+pub struct SepaDepositInformation {
+    pub iban: String,
+    pub swift: String,
+    pub bank_name: String,
+    pub bank_address: String,
+    pub bank_country_name: String,
+    pub account_name: String,
+    pub account_address: String,
+    pub reference: String,
+}
+
+/// A structure that represents a crypto deposit address
+/// <br>
+/// Deposits to currencies like XRP or XLM must include `destination_tag`. `warnings` should be shown to whoever makes the deposit
+#[derive(Debug, Deserialize)]
+pub struct CryptoAddress {
+    pub id: String,
+    pub address: String,
+    pub destination_tag: Option<String>,
+    pub network: Option<String>,
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub updated_at: Option<DateTime<Utc>>,
+    pub uri_scheme: Option<String>,
+    pub deposit_uri: Option<String>,
+    pub legacy_address: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<AddressWarning>,
+    pub exchange_deposit_address: Option<bool>,
+}
+
+/// A structure that represents a warning about a `CryptoAddress`
+#[derive(Debug, Deserialize)]
+pub struct AddressWarning {
+    pub title: String,
+    pub details: String,
+    pub image_url: Option<String>,
+}
+
 /// A structure represents a single profile
 #[derive(Debug, Deserialize)]
 pub struct Profile {
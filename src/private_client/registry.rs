@@ -0,0 +1,132 @@
+use super::{OrderInfo, PlacedOrder};
+use crate::websocket::Message;
+use std::collections::HashMap;
+
+/// `OrderRegistry` maps the client_oids of your orders to their server-assigned ids and last known `OrderInfo`
+/// <br>
+/// Insert the orders placed with `PrivateClient::place_order_with_client_oid`, then find the order of a fill, a `done` message or a cancel
+/// by its order id without another request. Orders placed elsewhere are inserted from the `received` messages of the `user` or `full` channel.
+/// <br>
+/// ~~~~ignore
+/// let mut registry = OrderRegistry::new();
+/// let placed = client.place_order_with_client_oid(order).await.unwrap();
+/// registry.insert(&placed);
+/// for fill in client.get_fill_by_order_id(&placed.id, None, None, None).await.unwrap().items {
+///     let client_oid = registry.client_oid(&fill.order_id).unwrap();
+/// }
+/// ~~~~
+#[derive(Debug, Default)]
+pub struct OrderRegistry {
+    orders: HashMap<String, RegisteredOrder>,
+    // client_oids by order id
+    client_oids: HashMap<String, String>,
+}
+
+/// A structure that represents an order of an `OrderRegistry`
+/// <br>
+/// `info` is `None` until the registry is updated with the `OrderInfo` of the order
+#[derive(Debug)]
+pub struct RegisteredOrder {
+    pub client_oid: String,
+    pub id: String,
+    pub info: Option<OrderInfo>,
+}
+
+impl OrderRegistry {
+    /// Creates an empty `OrderRegistry`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a placed order
+    /// <br>
+    /// A client_oid that is reused for another order is moved to the new order, forgetting the old one
+    pub fn insert(&mut self, placed: &PlacedOrder) -> &RegisteredOrder {
+        self.register(&placed.client_oid, &placed.id)
+    }
+
+    /// Stores `info` as the last known state of its order
+    /// <br>
+    /// An order that is not registered yet is inserted if it has a client_oid. Returns `false` if the order was ignored
+    pub fn update(&mut self, info: OrderInfo) -> bool {
+        let client_oid = match info
+            .client_oid
+            .clone()
+            .or_else(|| self.client_oids.get(&info.id).cloned())
+        {
+            Some(client_oid) => client_oid,
+            None => return false,
+        };
+        let id = info.id.clone();
+        self.register(&client_oid, &id).info = Some(info);
+        true
+    }
+
+    /// Inserts the order of a `received` message that has a client_oid, other messages are ignored
+    /// <br>
+    /// Returns `true` if an order was inserted
+    pub fn apply(&mut self, message: &Message) -> bool {
+        match message {
+            Message::Received(received) => match &received.client_oid {
+                Some(client_oid) => {
+                    self.register(client_oid, &received.order_id);
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Gets an order by its client_oid
+    pub fn get(&self, client_oid: &str) -> Option<&RegisteredOrder> {
+        self.orders.get(client_oid)
+    }
+
+    /// Gets an order by its server-assigned id
+    pub fn get_by_order_id(&self, order_id: &str) -> Option<&RegisteredOrder> {
+        self.client_oid(order_id)
+            .and_then(|client_oid| self.orders.get(client_oid))
+    }
+
+    /// Gets the client_oid of the order with the server-assigned id `order_id`
+    pub fn client_oid(&self, order_id: &str) -> Option<&str> {
+        self.client_oids.get(order_id).map(String::as_str)
+    }
+
+    /// Removes an order by its client_oid, e.g. once it is done
+    pub fn remove(&mut self, client_oid: &str) -> Option<RegisteredOrder> {
+        let order = self.orders.remove(client_oid)?;
+        self.client_oids.remove(&order.id);
+        Some(order)
+    }
+
+    /// The number of registered orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Whether no order is registered
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    fn register(&mut self, client_oid: &str, id: &str) -> &mut RegisteredOrder {
+        let order = self
+            .orders
+            .entry(client_oid.to_string())
+            .or_insert_with(|| RegisteredOrder {
+                client_oid: client_oid.to_string(),
+                id: id.to_string(),
+                info: None,
+            });
+        if order.id != id {
+            self.client_oids.remove(&order.id);
+            order.id = id.to_string();
+            order.info = None;
+        }
+        self.client_oids
+            .insert(id.to_string(), client_oid.to_string());
+        order
+    }
+}
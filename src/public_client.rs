@@ -1,15 +1,23 @@
 use super::{
-    deserialize_response, deserialize_to_date, COINBASE_API_URL, COINBASE_SANDBOX_API_URL,
+    deserialize_page_response, deserialize_response, deserialize_to_date, stream_pages, CreatedAt,
+    Page, COINBASE_API_URL, COINBASE_SANDBOX_API_URL,
 };
+use crate::private_client::OrderSide;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{send_with_retry, RetryPolicy};
+use crate::transport::{HeaderMap, Method, Request, ReqwestTransport, Response, Transport};
 use crate::{configure_pagination, error::Error};
 use chrono::{DateTime, Utc};
-use reqwest;
+use futures::stream::BoxStream;
 use serde;
+use std::sync::Arc;
 
 /// `PublicClient provides public market data
+#[derive(Clone)]
 pub struct PublicClient {
-    reqwest_client: reqwest::Client,
-    url: &'static str,
+    transport: Arc<dyn Transport>,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
 }
 
 impl PublicClient {
@@ -19,56 +27,100 @@ impl PublicClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<T, Error>
+    ) -> Result<Page<T>, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let params = configure_pagination(before, after, limit);
-        self.get(&format!("{}{}", path, params)).await
+        let path = format!("{}{}", path, configure_pagination(before, after, limit));
+        let response = self.get_response(&path).await?;
+        deserialize_page_response(Method::GET, &path, response)
     }
 
     async fn get<T>(&self, path: &str) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let response = self
-            .reqwest_client
-            .get(format!("{}{}", self.url, path))
-            .header(reqwest::header::USER_AGENT, "coinbase_client")
-            .send()
-            .await?;
-        deserialize_response(response).await
+        deserialize_response(Method::GET, path, self.get_response(path).await?)
+    }
+
+    async fn get_response(&self, path: &str) -> Result<Response, Error> {
+        send_with_retry(self.retry_policy.as_ref(), || async move {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            self.transport
+                .send(Request {
+                    method: Method::GET,
+                    path: path.to_string(),
+                    headers: HeaderMap::new(),
+                    body: None,
+                })
+                .await
+                .map_err(|err| err.with_context(Method::GET, path, None))
+        })
+        .await
     }
 
     /// Creates a `PublicClient`
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// ~~~~
     pub fn new() -> Self {
-        Self {
-            reqwest_client: reqwest::Client::new(),
-            url: COINBASE_API_URL,
-        }
+        Self::with_transport(ReqwestTransport::new(COINBASE_API_URL))
     }
 
     /// Creates a `PublicClient` to be used with the coinbase pro sandbox API
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// ~~~~
     pub fn new_sandbox() -> Self {
+        Self::with_transport(ReqwestTransport::new(COINBASE_SANDBOX_API_URL))
+    }
+
+    /// Creates a `PublicClient` that sends its requests through `transport`
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
         Self {
-            reqwest_client: reqwest::Client::new(),
-            url: COINBASE_SANDBOX_API_URL,
+            transport: Arc::new(transport),
+            rate_limiter: None,
+            retry_policy: None,
         }
     }
 
+    /// Throttles the requests of this client and its clones with `rate_limiter`
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PublicClient::new().with_rate_limiter(RateLimiter::public());
+    /// ~~~~
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Gets the `RateLimiter` of this client, if any
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Retries requests that fail with a transient error according to `retry_policy`
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PublicClient::new().with_retry_policy(RetryPolicy::default());
+    /// ~~~~
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
     /// Get a list of available currency pairs for trading
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-products)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let products = client.get_products().await.unwrap();
     /// ~~~~
@@ -81,7 +133,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-single-product)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let product = client.get_product("BTC-USD").await.unwrap();
     /// ~~~~
@@ -108,12 +160,12 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-product-order-book)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let order_book = client.get_product_order_book("BTC-USD").await.unwrap();
     /// ~~~~
     pub async fn get_product_order_book(&self, id: &str) -> Result<OrderBook<BookEntry>, Error> {
-        Ok(self.get_order_book(id, OrderLevel::One).await?)
+        self.get_order_book(id, OrderLevel::One).await
     }
 
     /// Get a list of open orders for a product
@@ -122,7 +174,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-product-order-book)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let order_book = client
     ///     .get_product_order_book_top50("BTC-USD")
@@ -133,7 +185,7 @@ impl PublicClient {
         &self,
         id: &str,
     ) -> Result<OrderBook<BookEntry>, Error> {
-        Ok(self.get_order_book(id, OrderLevel::Two).await?)
+        self.get_order_book(id, OrderLevel::Two).await
     }
 
     /// Get a list of open orders for a product
@@ -142,7 +194,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-product-order-book)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let order_book = client.get_product_order_book_all("BTC-USD").await.unwrap();
     /// ~~~~
@@ -161,7 +213,7 @@ impl PublicClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let ticker = client
     ///     .get_product_ticker("BTC-USD", Some("30902419"), None, None)
@@ -175,8 +227,9 @@ impl PublicClient {
         after: Option<&str>,
         limit: Option<u16>,
     ) -> Result<Ticker, Error> {
+        let params = configure_pagination(before, after, limit);
         let ticker = self
-            .get_paginated(&format!("/products/{}/ticker?", id), before, after, limit)
+            .get(&format!("/products/{}/ticker?{}", id, params))
             .await?;
         Ok(ticker)
     }
@@ -196,7 +249,7 @@ impl PublicClient {
     /// <br>
     /// This request is [paginated](https://docs.pro.coinbase.com/#pagination)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let trades = client
     ///     .get_product_trades("BTC-USD", None, Some("30898635"), Some(100))
@@ -209,13 +262,41 @@ impl PublicClient {
         before: Option<&str>,
         after: Option<&str>,
         limit: Option<u16>,
-    ) -> Result<Vec<Trade>, Error> {
-        let trades: Vec<Trade> = self
+    ) -> Result<Page<Trade>, Error> {
+        let trades: Page<Trade> = self
             .get_paginated(&format!("/products/{}/trades?", id), before, after, limit)
             .await?;
         Ok(trades)
     }
 
+    /// Stream the trades for a product, newest first
+    /// <br>
+    /// Follows the `after` cursor until the last page, or until a trade older than `until`
+    /// <br>
+    /// [api docs](https://docs.pro.coinbase.com/#get-trades)
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PublicClient::new();
+    /// let mut trades = client.stream_product_trades("BTC-USD", Some(1000), Some(Utc::now() - Duration::hours(1)));
+    /// while let Some(trade) = trades.next().await {
+    ///     println!("{:?}", trade.unwrap());
+    /// }
+    /// ~~~~
+    pub fn stream_product_trades<'a>(
+        &'a self,
+        id: &'a str,
+        limit: Option<u16>,
+        until: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<Trade, Error>> {
+        stream_pages(
+            move |after: Option<String>| async move {
+                self.get_product_trades(id, None, after.as_deref(), limit)
+                    .await
+            },
+            until,
+        )
+    }
+
     /// get historic rates for a product
     /// <br>
     /// <br>
@@ -229,7 +310,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-historic-rates)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let historical_rates = client
     ///     .get_product_historic_rates("BTC-USD", None, None, Some(Granularity::OneMinute))
@@ -272,7 +353,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-24hr-stats)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let twenty_four_hour_stats = client.get_product_24hr_stats("BTC-USD").await.unwrap();
     /// ~~~~
@@ -285,7 +366,7 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-currencies)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let currencies = client.get_currencies().await.unwrap();
     /// ~~~~
@@ -298,19 +379,19 @@ impl PublicClient {
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#get-a-currency)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let currency = client.get_currency("LINK").await.unwrap();
     /// ~~~~
     pub async fn get_currency(&self, id: &str) -> Result<Currency, Error> {
-        Ok(self.get(&format!("/currencies/{}", id)).await?)
+        self.get(&format!("/currencies/{}", id)).await
     }
 
     /// Get the API server time
     /// <br>
     /// [api docs](https://docs.pro.coinbase.com/#time)
     /// <br>
-    /// ~~~~
+    /// ~~~~ignore
     /// let client = PublicClient::new();
     /// let time = client.get_time().await.unwrap();
     /// ~~~~
@@ -320,6 +401,12 @@ impl PublicClient {
     }
 }
 
+impl Default for PublicClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A structure that represents a product
 #[derive(serde::Deserialize, Debug)]
 pub struct Product {
@@ -371,7 +458,13 @@ pub struct Trade {
     pub trade_id: u64,
     pub price: String,
     pub size: String,
-    pub side: String,
+    pub side: OrderSide,
+}
+
+impl CreatedAt for Trade {
+    fn created_at(&self) -> DateTime<Utc> {
+        self.time
+    }
 }
 
 /// A structure that represents latest trades for a product
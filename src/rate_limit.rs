@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// `RateLimiter` is a token bucket that throttles the requests of a client
+/// <br>
+/// Clones share the same bucket, so one limiter can throttle every clone of a client, or several clients using the same API key. Requests wait for capacity instead of failing
+/// <br>
+/// [API docs](https://docs.pro.coinbase.com/#rate-limits)
+/// <br>
+/// ~~~~ignore
+/// let limiter = RateLimiter::private();
+/// let client = PrivateClient::new(secret, passphrase, key).with_rate_limiter(limiter.clone());
+/// let accounts = client.get_accounts().await.unwrap();
+/// println!("{} requests available", limiter.available());
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    // negative while requests are waiting for capacity
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+    }
+}
+
+impl RateLimiter {
+    /// Creates a `RateLimiter` that allows `rate` requests per second with bursts of up to `burst` requests
+    /// <br>
+    /// Panics if `rate` is not positive
+    pub fn new(rate: f64, burst: u32) -> Self {
+        assert!(rate > 0.0, "rate must be positive");
+        let burst = f64::from(burst.max(1));
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                rate,
+                burst,
+                tokens: burst,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Creates a `RateLimiter` matching the limit of public endpoints, 3 requests per second with bursts of up to 6
+    pub fn public() -> Self {
+        Self::new(3.0, 6)
+    }
+
+    /// Creates a `RateLimiter` matching the limit of private endpoints, 5 requests per second with bursts of up to 10
+    pub fn private() -> Self {
+        Self::new(5.0, 10)
+    }
+
+    /// Waits until a request can be sent and takes its token
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            bucket.refill();
+            bucket.tokens -= 1.0;
+            // waiting requests are served in the order they reserved their token
+            if bucket.tokens < 0.0 {
+                Some(Duration::from_secs_f64(-bucket.tokens / bucket.rate))
+            } else {
+                None
+            }
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token if a request can be sent now, without waiting
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Gets the number of requests that can be sent now without waiting
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        bucket.tokens.max(0.0) as u32
+    }
+
+    /// Gets the time until a request can be sent without waiting
+    pub fn wait_time(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        if bucket.tokens >= 1.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate)
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::Error;
+use crate::transport::Response;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Retry settings of `PublicClient` and `PrivateClient`
+/// <br>
+/// Requests that fail with a 429 or 5xx status or a connection error are retried up to `max_retries` times. The delay
+/// before each retry is the `Retry-After` header of the response if present, capped at `max_backoff`, otherwise it doubles
+/// from `initial_backoff` up to `max_backoff` with a random jitter of up to half the delay subtracted.
+/// <br>
+/// GET requests are always retried. `PrivateClient::place_order` is only retried when the order has a `client_oid`,
+/// and looks the order up before resubmitting it so that a retry never creates a duplicate order. Other requests are not retried.
+/// <br>
+/// ~~~~ignore
+/// let client = PublicClient::new().with_retry_policy(RetryPolicy::default());
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    // the delay before retry number `retry`, counting from 0
+    pub(crate) fn delay(&self, retry: u32, response: Option<&Response>) -> Duration {
+        let retry_after = response
+            .and_then(|response| response.headers.get(reqwest::header::RETRY_AFTER))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        match retry_after {
+            Some(seconds) => Duration::from_secs(seconds).min(self.max_backoff),
+            None => backoff(self.initial_backoff, self.max_backoff, retry),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+// the delay before attempt number `attempt`, counting from 0, of the request retries and the websocket reconnects
+// it doubles from `initial` up to `max`, with a random jitter of up to half the delay subtracted
+pub(crate) fn backoff(initial: Duration, max: Duration, attempt: u32) -> Duration {
+    let delay = initial
+        .checked_mul(1 << attempt.min(16))
+        .unwrap_or(max)
+        .min(max);
+    let random = RandomState::new().build_hasher().finish();
+    delay - (delay / 2).mul_f64(random as f64 / u64::MAX as f64)
+}
+
+// whether a request that ended with `result` may succeed if sent again
+pub(crate) fn is_transient(result: &Result<Response, Error>) -> bool {
+    match result {
+        Ok(response) => response.status == 429 || response.status >= 500,
+        Err(err) => err.is_retryable(),
+    }
+}
+
+// sends a request with `send` until it succeeds, fails permanently or `policy` runs out of retries
+pub(crate) async fn send_with_retry<F, Fut>(
+    policy: Option<&RetryPolicy>,
+    mut send: F,
+) -> Result<Response, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Response, Error>>,
+{
+    let mut retry = 0;
+    loop {
+        let result = send().await;
+        match policy {
+            Some(policy) if retry < policy.max_retries && is_transient(&result) => {
+                tokio::time::sleep(policy.delay(retry, result.as_ref().ok())).await;
+                retry += 1;
+            }
+            _ => return result,
+        }
+    }
+}
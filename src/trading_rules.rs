@@ -0,0 +1,152 @@
+use crate::error::{Error, ErrorKind, ValidationError};
+use crate::fixed::FixedDecimal;
+use crate::private_client::OrderSide;
+use crate::public_client::Product;
+use crate::Amount;
+
+/// The direction to round a price or size to an increment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the multiple at or below the value
+    Down,
+    /// To the multiple at or above the value
+    Up,
+    /// To the closest multiple, halfway values are rounded up
+    Nearest,
+}
+
+impl Rounding {
+    /// Rounds the price of a limit order on `side` toward the passive side of the book: down for buys and up for sells
+    pub fn passive(side: &OrderSide) -> Self {
+        match side {
+            OrderSide::Sell => Self::Up,
+            _ => Self::Down,
+        }
+    }
+}
+
+/// The increments of a `Product` that prices and sizes must be multiples of
+/// <br>
+/// Rounds prices computed as floats in exact decimal arithmetic, digits past the 18th decimal place are dropped. Sizes and funds are rounded down by default so that an order never
+/// spends more than intended, and limit prices toward the passive side of the book so that an order never crosses further than intended
+/// <br>
+/// ~~~~ignore
+/// let rules = PublicClient::new().get_product("BTC-USD").await.unwrap().trading_rules().unwrap();
+/// let price = rules.round_limit_price(&OrderSide::Buy, 36000.0 * 1.0015).unwrap(); // "36054.00"
+/// let size = rules.round_size(100.0 / 36054.0).unwrap(); // "0.00277361"
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct TradingRules {
+    product_id: String,
+    base_increment: FixedDecimal,
+    quote_increment: FixedDecimal,
+}
+
+/// A price, size or funds to round with `TradingRules`
+/// <br>
+/// Created from an `f64` computed by a strategy, or from a `&str` or `String` like `"36054.001"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unrounded(String);
+
+impl From<f64> for Unrounded {
+    fn from(value: f64) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<&str> for Unrounded {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<String> for Unrounded {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&String> for Unrounded {
+    fn from(value: &String) -> Self {
+        Self(value.clone())
+    }
+}
+
+impl TradingRules {
+    /// Gets the trading rules of `product`, returning a `Validation` error if its increments are not decimal numbers
+    pub fn new(product: &Product) -> Result<Self, Error> {
+        Ok(Self {
+            product_id: product.id.clone(),
+            base_increment: increment("base_increment", &product.base_increment)?,
+            quote_increment: increment("quote_increment", &product.quote_increment)?,
+        })
+    }
+
+    /// The product of the rules
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    /// Rounds `price` to the quote increment in the direction of `rounding`
+    pub fn round_price(
+        &self,
+        price: impl Into<Unrounded>,
+        rounding: Rounding,
+    ) -> Result<Amount, Error> {
+        round("price", price.into(), &self.quote_increment, rounding)
+    }
+
+    /// Rounds the price of a limit order on `side` to the quote increment, toward the passive side of the book
+    pub fn round_limit_price(
+        &self,
+        side: &OrderSide,
+        price: impl Into<Unrounded>,
+    ) -> Result<Amount, Error> {
+        self.round_price(price, Rounding::passive(side))
+    }
+
+    /// Rounds `size` down to the base increment
+    pub fn round_size(&self, size: impl Into<Unrounded>) -> Result<Amount, Error> {
+        round("size", size.into(), &self.base_increment, Rounding::Down)
+    }
+
+    /// Rounds `funds` down to the quote increment
+    pub fn round_funds(&self, funds: impl Into<Unrounded>) -> Result<Amount, Error> {
+        round("funds", funds.into(), &self.quote_increment, Rounding::Down)
+    }
+}
+
+impl Product {
+    /// Gets the increments of the product as `TradingRules`
+    pub fn trading_rules(&self) -> Result<TradingRules, Error> {
+        TradingRules::new(self)
+    }
+}
+
+fn invalid_number(field: &'static str, value: String) -> Error {
+    Error::new(ErrorKind::Validation(ValidationError::InvalidNumber {
+        field,
+        value,
+    }))
+}
+
+fn increment(field: &'static str, value: &str) -> Result<FixedDecimal, Error> {
+    match FixedDecimal::parse(value) {
+        Some(increment) => Ok(increment.normalize()),
+        None => Err(invalid_number(field, value.to_string())),
+    }
+}
+
+fn round(
+    field: &'static str,
+    value: Unrounded,
+    increment: &FixedDecimal,
+    rounding: Rounding,
+) -> Result<Amount, Error> {
+    match FixedDecimal::parse_truncated(&value.0) {
+        Some(number) => Ok(Amount::from(
+            number.round_to(increment, rounding).to_string(),
+        )),
+        None => Err(invalid_number(field, value.0)),
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::Error;
+use futures::future::{BoxFuture, FutureExt};
+pub use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderValue, USER_AGENT};
+pub use reqwest::Method;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A structure that represents a request to the REST API
+/// <br>
+/// `path` is relative to the base URL of the API and includes the query string
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+}
+
+/// A structure that represents a response from the REST API
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Response {
+    /// `true` if the status code is in the 200-299 range
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// `Transport` sends the requests of `PublicClient` and `PrivateClient`
+/// <br>
+/// `ReqwestTransport` is used by default. Implement `Transport` to inject an in-memory fake for testing without a network
+/// <br>
+/// ~~~~ignore
+/// struct Fake;
+///
+/// impl Transport for Fake {
+///     fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+///         assert_eq!(request.path, "/time");
+///         async move {
+///             Ok(Response {
+///                 status: 200,
+///                 headers: HeaderMap::new(),
+///                 body: r#"{"iso":"2015-01-07T23:47:25.201Z","epoch":1420674445.201}"#.to_string(),
+///             })
+///         }
+///         .boxed()
+///     }
+/// }
+///
+/// let client = PublicClient::with_transport(Fake);
+/// let time = client.get_time().await.unwrap();
+/// ~~~~
+pub trait Transport: Send + Sync {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>>;
+}
+
+impl<T> Transport for Arc<T>
+where
+    T: Transport + ?Sized,
+{
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+        (**self).send(request)
+    }
+}
+
+/// `ReqwestTransport` sends requests to a base URL with a `reqwest::Client`
+/// <br>
+/// Use `ClientBuilder` to configure timeouts, a proxy, the user agent and default headers
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+    pub(crate) client: reqwest::Client,
+    pub(crate) url: String,
+    // added to every request that does not set them
+    pub(crate) headers: HeaderMap,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl ReqwestTransport {
+    /// Creates a `ReqwestTransport` for the API at `url`
+    pub fn new(url: &str) -> Self {
+        Self::with_client(reqwest::Client::new(), url)
+    }
+
+    /// Creates a `ReqwestTransport` for the API at `url` that sends requests with `client`
+    pub fn with_client(client: reqwest::Client, url: &str) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("coinbase-client"));
+        Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            headers,
+            timeout: None,
+        }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+        let mut headers = request.headers;
+        for (name, value) in self.headers.iter() {
+            if !headers.contains_key(name) {
+                headers.insert(name, value.clone());
+            }
+        }
+        let builder = self
+            .client
+            .request(request.method, format!("{}{}", self.url, request.path))
+            .headers(headers);
+        let builder = match self.timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        };
+        let builder = match request.body {
+            Some(body) => builder.body(body),
+            None => builder,
+        };
+        async move {
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let headers = response.headers().clone();
+            let body = response.text().await?;
+            Ok(Response {
+                status,
+                headers,
+                body,
+            })
+        }
+        .boxed()
+    }
+}
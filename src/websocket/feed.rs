@@ -0,0 +1,452 @@
+use super::{Channel, Message};
+use crate::error::Error;
+use crate::private_client::PrivateClient;
+use crate::retry::backoff;
+use crate::{COINBASE_SANDBOX_WEBSOCKET_URL, COINBASE_WEBSOCKET_URL};
+use futures::future::BoxFuture;
+use futures::{ready, FutureExt, SinkExt, Stream, StreamExt};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::{Instant, Sleep};
+use tokio_tungstenite::tungstenite::{self, Message as WsMessage};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// `WebsocketFeed` provides real-time market data updates from the websocket feed
+/// <br>
+/// Messages are received by polling the feed as a `futures::Stream`
+/// <br>
+/// By default the stream ends when the connection is closed. A feed created `with_reconnect` instead emits
+/// `Message::Disconnected`, reconnects with exponential backoff and replays its subscriptions before emitting `Message::Reconnected`.
+/// Subscriptions changed while reconnecting are sent once the new connection is up.
+pub struct WebsocketFeed {
+    url: String,
+    connection: Connection,
+    subscriptions: Subscriptions,
+    // credentials used to replay authenticated subscriptions
+    client: Option<PrivateClient>,
+    reconnect: Option<ReconnectOptions>,
+    idle: Option<Pin<Box<Sleep>>>,
+    attempt: u32,
+}
+
+enum Connection {
+    Open(Box<Socket>),
+    // resolves to the new socket and the subscriptions replayed on it
+    Reconnecting(BoxFuture<'static, Result<(Socket, Subscriptions), Error>>),
+    Closed,
+}
+
+type Subscriptions = BTreeMap<Channel, Subscription>;
+
+#[derive(Clone, Default, PartialEq, Eq)]
+struct Subscription {
+    product_ids: BTreeSet<String>,
+    authenticated: bool,
+}
+
+impl WebsocketFeed {
+    /// Connects to the websocket feed
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#websocket-feed)
+    /// <br>
+    /// ~~~~ignore
+    /// let mut feed = WebsocketFeed::connect().await.unwrap();
+    /// feed.subscribe(&["BTC-USD"], &[Channel::Ticker]).await.unwrap();
+    /// while let Some(message) = feed.next().await {
+    ///     println!("{:?}", message.unwrap());
+    /// }
+    /// ~~~~
+    pub async fn connect() -> Result<Self, Error> {
+        Self::connect_to(COINBASE_WEBSOCKET_URL).await
+    }
+
+    /// Connects to the coinbase pro sandbox websocket feed
+    pub async fn connect_sandbox() -> Result<Self, Error> {
+        Self::connect_to(COINBASE_SANDBOX_WEBSOCKET_URL).await
+    }
+
+    /// Connects to a websocket feed at `url`
+    pub async fn connect_to(url: &str) -> Result<Self, Error> {
+        let (socket, _) = tokio_tungstenite::connect_async(url).await?;
+        Ok(Self {
+            url: url.to_string(),
+            connection: Connection::Open(Box::new(socket)),
+            subscriptions: BTreeMap::new(),
+            client: None,
+            reconnect: None,
+            idle: None,
+            attempt: 0,
+        })
+    }
+
+    /// Reconnect automatically when the connection is closed or no message is received within `options.heartbeat_timeout`
+    /// <br>
+    /// ~~~~ignore
+    /// let mut feed = WebsocketFeed::connect()
+    ///     .await
+    ///     .unwrap()
+    ///     .with_reconnect(ReconnectOptions::default());
+    /// feed.subscribe(&["BTC-USD"], &[Channel::Heartbeat, Channel::Level2]).await.unwrap();
+    /// while let Some(message) = feed.next().await {
+    ///     match message.unwrap() {
+    ///         Message::Disconnected => println!("connection lost"),
+    ///         // the level2 channel sends a new snapshot after the subscriptions are replayed
+    ///         Message::Reconnected => println!("connection restored"),
+    ///         message => println!("{:?}", message),
+    ///     }
+    /// }
+    /// ~~~~
+    pub fn with_reconnect(mut self, options: ReconnectOptions) -> Self {
+        self.reconnect = Some(options);
+        self
+    }
+
+    /// Subscribe to `channels` for each of `product_ids`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#subscribe)
+    pub async fn subscribe(
+        &mut self,
+        product_ids: &[&str],
+        channels: &[Channel],
+    ) -> Result<(), Error> {
+        self.send(&SubscribeMessage {
+            r#type: "subscribe",
+            product_ids,
+            channels,
+            auth: None,
+        })
+        .await?;
+        self.track(product_ids, channels, false);
+        Ok(())
+    }
+
+    /// Subscribe to `channels` for each of `product_ids`, signing the request with the credentials of `client`
+    /// <br>
+    /// Required for the `user` channel and to receive your own user and profile ids on the `full` channel
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#subscribe)
+    /// <br>
+    /// ~~~~ignore
+    /// let client = PrivateClient::new("tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==", "t9riylyad0r", "4a9f6de8bcdee641a0a207613dfb43ef");
+    /// let mut feed = WebsocketFeed::connect().await.unwrap();
+    /// feed.subscribe_authenticated(&["BTC-USD"], &[Channel::User], &client)
+    ///     .await
+    ///     .unwrap();
+    /// ~~~~
+    pub async fn subscribe_authenticated(
+        &mut self,
+        product_ids: &[&str],
+        channels: &[Channel],
+        client: &PrivateClient,
+    ) -> Result<(), Error> {
+        let auth = client.websocket_auth()?;
+        self.send(&SubscribeMessage {
+            r#type: "subscribe",
+            product_ids,
+            channels,
+            auth: Some(auth),
+        })
+        .await?;
+        self.client = Some(client.clone());
+        self.track(product_ids, channels, true);
+        Ok(())
+    }
+
+    /// Unsubscribe from `channels` for each of `product_ids`
+    /// <br>
+    /// [API docs](https://docs.pro.coinbase.com/#subscribe)
+    pub async fn unsubscribe(
+        &mut self,
+        product_ids: &[&str],
+        channels: &[Channel],
+    ) -> Result<(), Error> {
+        self.send(&SubscribeMessage {
+            r#type: "unsubscribe",
+            product_ids,
+            channels,
+            auth: None,
+        })
+        .await?;
+        self.untrack(product_ids, channels);
+        Ok(())
+    }
+
+    /// Closes the connection to the websocket feed, a reconnecting feed stops reconnecting
+    pub async fn close(&mut self) -> Result<(), Error> {
+        match std::mem::replace(&mut self.connection, Connection::Closed) {
+            Connection::Open(mut socket) => Ok(socket.as_mut().close(None).await?),
+            _ => Ok(()),
+        }
+    }
+
+    // sends a subscription change, which the caller tracks only once this succeeds
+    async fn send<T>(&mut self, message: &T) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        let text = serde_json::to_string(message)?;
+        match &mut self.connection {
+            Connection::Open(socket) => Ok(socket.send(WsMessage::Text(text)).await?),
+            // the pending reconnect applies the tracked change once the new connection is up
+            Connection::Reconnecting(_) => Ok(()),
+            Connection::Closed => Err(Error::from(tungstenite::Error::AlreadyClosed)),
+        }
+    }
+
+    fn track(&mut self, product_ids: &[&str], channels: &[Channel], authenticated: bool) {
+        for channel in channels {
+            let subscription = self.subscriptions.entry(*channel).or_default();
+            subscription
+                .product_ids
+                .extend(product_ids.iter().map(|id| id.to_string()));
+            subscription.authenticated |= authenticated;
+        }
+    }
+
+    fn untrack(&mut self, product_ids: &[&str], channels: &[Channel]) {
+        for channel in channels {
+            if let Some(subscription) = self.subscriptions.get_mut(channel) {
+                for product_id in product_ids {
+                    subscription.product_ids.remove(*product_id);
+                }
+                if product_ids.is_empty() || subscription.product_ids.is_empty() {
+                    self.subscriptions.remove(channel);
+                }
+            }
+        }
+    }
+
+    // waits out the backoff, connects and replays the subscriptions
+    fn reconnect_future(&self) -> BoxFuture<'static, Result<(Socket, Subscriptions), Error>> {
+        let delay = self
+            .reconnect
+            .as_ref()
+            .map(|options| backoff(options.initial_backoff, options.max_backoff, self.attempt))
+            .unwrap_or_default();
+        let url = self.url.clone();
+        let subscriptions = self.subscriptions.clone();
+        let client = self.client.clone();
+        async move {
+            tokio::time::sleep(delay).await;
+            let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+            resubscribe(
+                &mut socket,
+                &Subscriptions::new(),
+                &subscriptions,
+                client.as_ref(),
+            )
+            .await?;
+            Ok((socket, subscriptions))
+        }
+        .boxed()
+    }
+
+    // brings a new connection subscribed to `subscribed` up to date with the subscriptions changed while it was connecting
+    fn update_future(
+        &self,
+        mut socket: Socket,
+        subscribed: Subscriptions,
+    ) -> BoxFuture<'static, Result<(Socket, Subscriptions), Error>> {
+        let subscriptions = self.subscriptions.clone();
+        let client = self.client.clone();
+        async move {
+            resubscribe(&mut socket, &subscribed, &subscriptions, client.as_ref()).await?;
+            Ok((socket, subscriptions))
+        }
+        .boxed()
+    }
+
+    fn disconnect(&mut self) -> Poll<Option<Result<Message, Error>>> {
+        self.connection = Connection::Reconnecting(self.reconnect_future());
+        Poll::Ready(Some(Ok(Message::Disconnected)))
+    }
+
+    fn reset_idle(&mut self) {
+        if let (Some(idle), Some(options)) = (&mut self.idle, &self.reconnect) {
+            idle.as_mut()
+                .reset(Instant::now() + options.heartbeat_timeout);
+        }
+    }
+}
+
+impl Stream for WebsocketFeed {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            match &mut this.connection {
+                Connection::Open(socket) => {
+                    let frame = match socket.poll_next_unpin(cx) {
+                        Poll::Ready(frame) => frame,
+                        Poll::Pending => {
+                            let timeout = match &this.reconnect {
+                                Some(options) => options.heartbeat_timeout,
+                                None => return Poll::Pending,
+                            };
+                            let idle = this
+                                .idle
+                                .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+                            ready!(idle.as_mut().poll(cx));
+                            // missed heartbeats, the connection is considered lost
+                            return this.disconnect();
+                        }
+                    };
+                    this.reset_idle();
+                    match frame {
+                        Some(Ok(WsMessage::Text(text))) => {
+                            return Poll::Ready(Some(
+                                serde_json::from_str(&text).map_err(Error::from),
+                            ))
+                        }
+                        // ping, pong and binary frames carry no feed messages
+                        Some(Ok(WsMessage::Ping(_)))
+                        | Some(Ok(WsMessage::Pong(_)))
+                        | Some(Ok(WsMessage::Binary(_))) => continue,
+                        _ if this.reconnect.is_some() => return this.disconnect(),
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                        Some(Ok(WsMessage::Close(_))) | None => {
+                            this.connection = Connection::Closed;
+                            return Poll::Ready(None);
+                        }
+                    }
+                }
+                Connection::Reconnecting(future) => match ready!(future.poll_unpin(cx)) {
+                    Ok((socket, subscribed)) if subscribed != this.subscriptions => {
+                        this.connection =
+                            Connection::Reconnecting(this.update_future(socket, subscribed));
+                    }
+                    Ok((socket, _)) => {
+                        this.connection = Connection::Open(Box::new(socket));
+                        this.attempt = 0;
+                        this.reset_idle();
+                        return Poll::Ready(Some(Ok(Message::Reconnected)));
+                    }
+                    Err(e) => {
+                        this.attempt += 1;
+                        let exhausted = match this.reconnect.as_ref().and_then(|o| o.max_attempts) {
+                            Some(max_attempts) => this.attempt >= max_attempts,
+                            None => false,
+                        };
+                        if exhausted {
+                            this.connection = Connection::Closed;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                        this.connection = Connection::Reconnecting(this.reconnect_future());
+                    }
+                },
+                Connection::Closed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Reconnection settings of a `WebsocketFeed`
+/// <br>
+/// The delay before each attempt doubles from `initial_backoff` up to `max_backoff`, and a random jitter of up to
+/// half the delay is subtracted so that many clients do not reconnect at the same time.
+/// <br>
+/// `heartbeat_timeout` only makes sense when subscribed to a channel that sends messages regularly, such as `Channel::Heartbeat`
+#[derive(Clone, Debug)]
+pub struct ReconnectOptions {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub heartbeat_timeout: Duration,
+    /// Consecutive failed attempts before the feed gives up, `None` to retry forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectOptions {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            heartbeat_timeout: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+// sends the requests that change the subscriptions of `socket` from `from` to `to`
+async fn resubscribe(
+    socket: &mut Socket,
+    from: &Subscriptions,
+    to: &Subscriptions,
+    client: Option<&PrivateClient>,
+) -> Result<(), Error> {
+    for (channel, old) in from.iter() {
+        let removed: Vec<&str> = match to.get(channel) {
+            Some(new) => old
+                .product_ids
+                .difference(&new.product_ids)
+                .map(String::as_str)
+                .collect(),
+            None => old.product_ids.iter().map(String::as_str).collect(),
+        };
+        if !removed.is_empty() {
+            send_subscription(socket, "unsubscribe", &removed, *channel, None).await?;
+        }
+    }
+    for (channel, new) in to.iter() {
+        let added: Vec<&str> = match from.get(channel) {
+            // products subscribed without credentials are subscribed again with them
+            Some(old) if old.authenticated || !new.authenticated => new
+                .product_ids
+                .difference(&old.product_ids)
+                .map(String::as_str)
+                .collect(),
+            _ => new.product_ids.iter().map(String::as_str).collect(),
+        };
+        if !added.is_empty() {
+            let auth = match (client, new.authenticated) {
+                (Some(client), true) => Some(client.websocket_auth()?),
+                _ => None,
+            };
+            send_subscription(socket, "subscribe", &added, *channel, auth).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn send_subscription(
+    socket: &mut Socket,
+    r#type: &'static str,
+    product_ids: &[&str],
+    channel: Channel,
+    auth: Option<WebsocketAuth>,
+) -> Result<(), Error> {
+    let text = serde_json::to_string(&SubscribeMessage {
+        r#type,
+        product_ids,
+        channels: &[channel],
+        auth,
+    })?;
+    Ok(socket.send(WsMessage::Text(text)).await?)
+}
+
+#[derive(Serialize)]
+struct SubscribeMessage<'a> {
+    r#type: &'static str,
+    product_ids: &'a [&'a str],
+    channels: &'a [Channel],
+    #[serde(flatten)]
+    auth: Option<WebsocketAuth>,
+}
+
+/// A structure that represents the signed fields of an authenticated subscribe request
+/// <br>
+/// The signature is created over `GET/users/self/verify`
+#[derive(Serialize, Debug)]
+pub struct WebsocketAuth {
+    pub signature: String,
+    pub key: String,
+    pub passphrase: String,
+    pub timestamp: String,
+}
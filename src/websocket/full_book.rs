@@ -0,0 +1,336 @@
+use super::{BookLevel, Message, WebsocketFeed};
+use crate::error::Error;
+use crate::private_client::OrderSide;
+use crate::public_client::{FullBookEntry, OrderBook, PublicClient};
+use crate::{book_decimal, FixedDecimal};
+use futures::future::{self, Either};
+use futures::StreamExt;
+use std::collections::{BTreeMap, HashMap};
+use tokio_tungstenite::tungstenite;
+
+/// `FullOrderBook` is a level 3 order book built from the level 3 snapshot and the `full` channel
+/// <br>
+/// Messages applied before a snapshot is loaded are buffered. Loading the snapshot discards buffered messages
+/// at or below the snapshot sequence and applies the rest. Once synchronized, a gap in the message sequence
+/// returns `BookEvent::Resync` and the book starts buffering again until a new snapshot is loaded.
+/// <br>
+/// [API docs](https://docs.pro.coinbase.com/#real-time-order-book)
+/// <br>
+/// ~~~~ignore
+/// let client = PublicClient::new();
+/// let mut feed = WebsocketFeed::connect().await.unwrap();
+/// feed.subscribe(&["BTC-USD"], &[Channel::Full]).await.unwrap();
+/// let mut book = FullOrderBook::new("BTC-USD");
+/// book.sync(&mut feed, &client).await.unwrap();
+/// while let Some(message) = feed.next().await {
+///     if let BookEvent::Resync { .. } = book.apply(&message.unwrap()).unwrap() {
+///         book.sync(&mut feed, &client).await.unwrap();
+///     }
+/// }
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct FullOrderBook {
+    product_id: String,
+    sequence: Option<u64>,
+    buffer: Vec<Message>,
+    orders: HashMap<String, BookOrder>,
+    // order ids resting at each price, in time priority
+    bids: BTreeMap<FixedDecimal, Vec<String>>,
+    asks: BTreeMap<FixedDecimal, Vec<String>>,
+}
+
+impl FullOrderBook {
+    /// Creates an empty `FullOrderBook` for `product_id` that buffers messages until a snapshot is loaded
+    pub fn new(product_id: &str) -> Self {
+        Self {
+            product_id: product_id.to_string(),
+            sequence: None,
+            buffer: Vec::new(),
+            orders: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+
+    /// Loads a level 3 snapshot and applies the buffered messages that follow it
+    /// <br>
+    /// Returns `BookEvent::Resync` if the buffered messages do not continue from the snapshot sequence
+    pub fn load_snapshot(
+        &mut self,
+        snapshot: &OrderBook<FullBookEntry>,
+    ) -> Result<BookEvent, Error> {
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+        for (side, entries) in [
+            (OrderSide::Buy, &snapshot.bids),
+            (OrderSide::Sell, &snapshot.asks),
+        ]
+        .iter()
+        {
+            for entry in entries.iter() {
+                self.insert(
+                    &entry.order_id,
+                    side.clone(),
+                    book_decimal(&entry.price)?,
+                    book_decimal(&entry.size)?,
+                );
+            }
+        }
+        self.sequence = Some(snapshot.sequence);
+        let mut event = BookEvent::Applied;
+        for message in std::mem::take(&mut self.buffer) {
+            if let BookEvent::Resync { .. } = event {
+                // the book is buffering again, keep the rest for the next snapshot
+                self.buffer.push(message);
+                continue;
+            }
+            if let resync @ BookEvent::Resync { .. } = self.apply(&message)? {
+                event = resync;
+            }
+        }
+        Ok(event)
+    }
+
+    /// Fetches a level 3 snapshot with `client` and loads it, buffering messages from `feed` while the request is in flight
+    /// <br>
+    /// `feed` must already be subscribed to the `full` channel for the book's product
+    pub async fn sync(
+        &mut self,
+        feed: &mut WebsocketFeed,
+        client: &PublicClient,
+    ) -> Result<BookEvent, Error> {
+        self.sequence = None;
+        let product_id = self.product_id.clone();
+        let snapshot = client.get_product_order_book_all(&product_id);
+        futures::pin_mut!(snapshot);
+        let snapshot = loop {
+            match future::select(snapshot.as_mut(), feed.next()).await {
+                Either::Left((snapshot, _)) => break snapshot?,
+                Either::Right((Some(message), _)) => {
+                    self.apply(&message?)?;
+                }
+                Either::Right((None, _)) => {
+                    return Err(Error::from(tungstenite::Error::ConnectionClosed))
+                }
+            }
+        };
+        self.load_snapshot(&snapshot)
+    }
+
+    /// Applies a `full` channel message for the book's product, other messages are ignored
+    pub fn apply(&mut self, message: &Message) -> Result<BookEvent, Error> {
+        let sequence = match sequence(message) {
+            Some((product_id, sequence)) if product_id == self.product_id => sequence,
+            _ => return Ok(BookEvent::Ignored),
+        };
+        let last = match self.sequence {
+            Some(last) => last,
+            None => {
+                self.buffer.push(message.clone());
+                return Ok(BookEvent::Buffered);
+            }
+        };
+        if sequence <= last {
+            return Ok(BookEvent::Ignored);
+        }
+        if sequence != last + 1 {
+            self.sequence = None;
+            self.buffer.push(message.clone());
+            return Ok(BookEvent::Resync {
+                expected: last + 1,
+                received: sequence,
+            });
+        }
+        match message {
+            Message::Open(open) => {
+                self.insert(
+                    &open.order_id,
+                    open.side.clone(),
+                    book_decimal(&open.price)?,
+                    book_decimal(&open.remaining_size)?,
+                );
+            }
+            Message::Done(done) => self.remove(&done.order_id),
+            Message::Match(m) => {
+                let size = book_decimal(&m.size)?;
+                if let Some(order) = self.orders.get_mut(&m.maker_order_id) {
+                    order.size = if order.size > size {
+                        order.size - size
+                    } else {
+                        FixedDecimal::default()
+                    };
+                }
+            }
+            Message::Change(change) => {
+                if let (Some(order), Some(new_size)) =
+                    (self.orders.get_mut(&change.order_id), &change.new_size)
+                {
+                    order.size = book_decimal(new_size)?;
+                }
+            }
+            // received orders are not on the book until they are open
+            _ => {}
+        }
+        self.sequence = Some(sequence);
+        Ok(BookEvent::Applied)
+    }
+
+    /// The product of the order book
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    /// The sequence of the last applied message, `None` while waiting for a snapshot
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// An order resting on the book
+    pub fn order(&self, order_id: &str) -> Option<&BookOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Number of orders resting on the book
+    pub fn order_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// The highest bid, with the total size of the orders at that price
+    pub fn best_bid(&self) -> Option<BookLevel> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(price, ids)| self.level(price, ids))
+    }
+
+    /// The lowest ask, with the total size of the orders at that price
+    pub fn best_ask(&self) -> Option<BookLevel> {
+        self.asks
+            .iter()
+            .next()
+            .map(|(price, ids)| self.level(price, ids))
+    }
+
+    /// The best `depth` bid price levels, highest price first
+    pub fn bids(&self, depth: usize) -> Vec<BookLevel> {
+        self.bids
+            .iter()
+            .rev()
+            .take(depth)
+            .map(|(price, ids)| self.level(price, ids))
+            .collect()
+    }
+
+    /// The best `depth` ask price levels, lowest price first
+    pub fn asks(&self, depth: usize) -> Vec<BookLevel> {
+        self.asks
+            .iter()
+            .take(depth)
+            .map(|(price, ids)| self.level(price, ids))
+            .collect()
+    }
+
+    /// The orders resting at `price` on `side`, in time priority
+    pub fn orders_at(&self, side: OrderSide, price: FixedDecimal) -> Vec<&BookOrder> {
+        let levels = match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
+            OrderSide::Unknown(_) => return Vec::new(),
+        };
+        levels
+            .get(&price)
+            .map(|ids| ids.iter().filter_map(|id| self.orders.get(id)).collect())
+            .unwrap_or_default()
+    }
+
+    fn level(&self, price: &FixedDecimal, ids: &[String]) -> BookLevel {
+        BookLevel {
+            price: *price,
+            size: ids
+                .iter()
+                .filter_map(|id| self.orders.get(id))
+                .map(|order| order.size)
+                .sum(),
+        }
+    }
+
+    // a replayed order keeps its time priority if it is still at the same price, otherwise it is moved
+    fn insert(&mut self, order_id: &str, side: OrderSide, price: FixedDecimal, size: FixedDecimal) {
+        if let Some(order) = self.orders.get_mut(order_id) {
+            if order.side == side && order.price == price {
+                order.size = size;
+                return;
+            }
+            self.remove(order_id);
+        }
+        let levels = match side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+            // an order on neither side of the book cannot be matched
+            OrderSide::Unknown(_) => return,
+        };
+        levels.entry(price).or_default().push(order_id.to_string());
+        self.orders.insert(
+            order_id.to_string(),
+            BookOrder {
+                order_id: order_id.to_string(),
+                side,
+                price,
+                size,
+            },
+        );
+    }
+
+    fn remove(&mut self, order_id: &str) {
+        let order = match self.orders.remove(order_id) {
+            Some(order) => order,
+            None => return,
+        };
+        let levels = match order.side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+            OrderSide::Unknown(_) => return,
+        };
+        if let Some(ids) = levels.get_mut(&order.price) {
+            ids.retain(|id| id != order_id);
+            if ids.is_empty() {
+                levels.remove(&order.price);
+            }
+        }
+    }
+}
+
+/// A structure that represents an order resting on a `FullOrderBook`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookOrder {
+    pub order_id: String,
+    pub side: OrderSide,
+    pub price: FixedDecimal,
+    pub size: FixedDecimal,
+}
+
+/// Result of applying a message to a `FullOrderBook`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookEvent {
+    /// The message was applied to the book
+    Applied,
+    /// The message was buffered until a snapshot is loaded
+    Buffered,
+    /// The message was for another product, not sequenced, or at or below the book's sequence
+    Ignored,
+    /// A sequence gap was detected, a new snapshot must be loaded
+    Resync { expected: u64, received: u64 },
+}
+
+// product and sequence of the messages that change a level 3 order book
+fn sequence(message: &Message) -> Option<(&str, u64)> {
+    match message {
+        Message::Received(m) => Some((&m.product_id, m.sequence)),
+        Message::Open(m) => Some((&m.product_id, m.sequence)),
+        Message::Done(m) => Some((&m.product_id, m.sequence)),
+        Message::Match(m) => Some((&m.product_id, m.sequence)),
+        Message::Change(m) => Some((&m.product_id, m.sequence)),
+        _ => None,
+    }
+}
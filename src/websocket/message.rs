@@ -0,0 +1,286 @@
+use crate::private_client::OrderSide;
+use crate::{deserialize_option_to_date, deserialize_to_date, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Channels of the websocket feed
+/// <br>
+/// [API docs](https://docs.pro.coinbase.com/#channels)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Heartbeat,
+    Status,
+    Ticker,
+    Level2,
+    Matches,
+    Full,
+    User,
+}
+
+/// A message received from the websocket feed
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Subscriptions(Subscriptions),
+    Heartbeat(Heartbeat),
+    Status(Status),
+    Ticker(TickerUpdate),
+    Snapshot(Level2Snapshot),
+    #[serde(rename = "l2update")]
+    Level2Update(Level2Update),
+    Received(Received),
+    Open(Open),
+    Done(Done),
+    Match(Match),
+    LastMatch(Match),
+    Change(Change),
+    Activate(Activate),
+    Error(FeedError),
+    /// Emitted by a reconnecting `WebsocketFeed` when the connection is lost, never sent by the server
+    #[serde(skip_deserializing)]
+    Disconnected,
+    /// Emitted by a reconnecting `WebsocketFeed` once the connection is restored and the subscriptions are replayed
+    /// <br>
+    /// Messages may have been missed while disconnected, local order books should be resynchronized
+    #[serde(skip_deserializing)]
+    Reconnected,
+}
+
+/// A structure that represents the channels currently subscribed to
+#[derive(Deserialize, Clone, Debug)]
+pub struct Subscriptions {
+    pub channels: Vec<SubscribedChannel>,
+}
+
+/// A structure that represents a single subscribed channel
+#[derive(Deserialize, Clone, Debug)]
+pub struct SubscribedChannel {
+    pub name: Channel,
+    pub product_ids: Vec<String>,
+}
+
+/// A structure that represents a heartbeat message
+#[derive(Deserialize, Clone, Debug)]
+pub struct Heartbeat {
+    pub sequence: u64,
+    pub last_trade_id: u64,
+    pub product_id: String,
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+}
+
+/// A structure that represents the status of all products and currencies
+#[derive(Deserialize, Clone, Debug)]
+pub struct Status {
+    pub products: Vec<StatusProduct>,
+    pub currencies: Vec<StatusCurrency>,
+}
+
+/// A structure that represents a product on the status channel
+#[derive(Deserialize, Clone, Debug)]
+pub struct StatusProduct {
+    pub id: String,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub base_min_size: String,
+    pub base_max_size: String,
+    pub base_increment: String,
+    pub quote_increment: String,
+    pub display_name: String,
+    pub status: String,
+    pub status_message: Option<String>,
+    pub min_market_funds: String,
+    pub max_market_funds: String,
+    pub post_only: bool,
+    pub limit_only: bool,
+    pub cancel_only: bool,
+}
+
+/// A structure that represents a currency on the status channel
+#[derive(Deserialize, Clone, Debug)]
+pub struct StatusCurrency {
+    pub id: String,
+    pub name: String,
+    pub min_size: String,
+    pub status: String,
+    pub status_message: Option<String>,
+    pub max_precision: String,
+    pub convertible_to: Option<Vec<String>>,
+    pub details: Json,
+}
+
+/// A structure that represents a ticker message
+#[derive(Deserialize, Clone, Debug)]
+pub struct TickerUpdate {
+    pub sequence: u64,
+    pub product_id: String,
+    pub price: String,
+    pub open_24h: String,
+    pub volume_24h: String,
+    pub low_24h: String,
+    pub high_24h: String,
+    pub volume_30d: String,
+    pub best_bid: String,
+    pub best_ask: String,
+    pub side: Option<OrderSide>,
+    #[serde(default, deserialize_with = "deserialize_option_to_date")]
+    pub time: Option<DateTime<Utc>>,
+    pub trade_id: Option<u64>,
+    pub last_size: Option<String>,
+}
+
+/// A structure that represents the level 2 order book snapshot sent after subscribing
+#[derive(Deserialize, Clone, Debug)]
+pub struct Level2Snapshot {
+    pub product_id: String,
+    pub bids: Vec<Level2Entry>,
+    pub asks: Vec<Level2Entry>,
+}
+
+/// A structure that represents a price level, sent as `[price, size]`
+#[derive(Deserialize, Clone, Debug)]
+pub struct Level2Entry {
+    pub price: String,
+    pub size: String,
+}
+
+/// A structure that represents changes to the level 2 order book
+#[derive(Deserialize, Clone, Debug)]
+pub struct Level2Update {
+    pub product_id: String,
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub changes: Vec<Level2Change>,
+}
+
+/// A structure that represents a changed price level, sent as `[side, price, size]`
+/// <br>
+/// A size of "0" means the price level can be removed
+#[derive(Deserialize, Clone, Debug)]
+pub struct Level2Change {
+    pub side: OrderSide,
+    pub price: String,
+    pub size: String,
+}
+
+/// A structure that represents an order accepted by the matching engine
+#[derive(Deserialize, Clone, Debug)]
+pub struct Received {
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub product_id: String,
+    pub sequence: u64,
+    pub order_id: String,
+    pub size: Option<String>,
+    pub price: Option<String>,
+    pub funds: Option<String>,
+    pub side: OrderSide,
+    pub order_type: String,
+    pub client_oid: Option<String>,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+}
+
+/// A structure that represents an order now resting on the order book
+#[derive(Deserialize, Clone, Debug)]
+pub struct Open {
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub product_id: String,
+    pub sequence: u64,
+    pub order_id: String,
+    pub price: String,
+    pub remaining_size: String,
+    pub side: OrderSide,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+}
+
+/// A structure that represents an order no longer on the order book
+#[derive(Deserialize, Clone, Debug)]
+pub struct Done {
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub product_id: String,
+    pub sequence: u64,
+    pub price: Option<String>,
+    pub order_id: String,
+    pub reason: DoneReason,
+    pub side: OrderSide,
+    pub remaining_size: Option<String>,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+}
+
+string_enum! {
+    /// Reason an order is done
+    pub enum DoneReason {
+        Filled => "filled",
+        Canceled => "canceled",
+    }
+}
+
+/// A structure that represents a trade between two orders
+/// <br>
+/// On the `user` channel and the authenticated `full` channel the user and profile ids of your side of the trade are included
+#[derive(Deserialize, Clone, Debug)]
+pub struct Match {
+    pub trade_id: u64,
+    pub sequence: u64,
+    pub maker_order_id: String,
+    pub taker_order_id: String,
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub product_id: String,
+    pub size: String,
+    pub price: String,
+    pub side: OrderSide,
+    pub taker_user_id: Option<String>,
+    pub taker_profile_id: Option<String>,
+    pub maker_user_id: Option<String>,
+    pub maker_profile_id: Option<String>,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+}
+
+/// A structure that represents an order changed by self-trade prevention
+#[derive(Deserialize, Clone, Debug)]
+pub struct Change {
+    #[serde(deserialize_with = "deserialize_to_date")]
+    pub time: DateTime<Utc>,
+    pub sequence: u64,
+    pub order_id: String,
+    pub product_id: String,
+    pub new_size: Option<String>,
+    pub old_size: Option<String>,
+    pub new_funds: Option<String>,
+    pub old_funds: Option<String>,
+    pub price: Option<String>,
+    pub side: OrderSide,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+}
+
+/// A structure that represents an activated stop order
+#[derive(Deserialize, Clone, Debug)]
+pub struct Activate {
+    pub product_id: String,
+    pub timestamp: String,
+    pub user_id: Option<String>,
+    pub profile_id: Option<String>,
+    pub order_id: String,
+    pub stop_type: String,
+    pub side: OrderSide,
+    pub stop_price: String,
+    pub size: Option<String>,
+    pub funds: Option<String>,
+    pub private: Option<bool>,
+}
+
+/// A structure that represents an error sent by the websocket feed
+#[derive(Deserialize, Clone, Debug)]
+pub struct FeedError {
+    pub message: String,
+    pub reason: Option<String>,
+}
@@ -0,0 +1,9 @@
+pub use feed::*;
+pub use full_book::*;
+pub use message::*;
+pub use order_book::*;
+mod feed;
+mod full_book;
+mod message;
+mod order_book;
+mod size_tree;
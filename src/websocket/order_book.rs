@@ -0,0 +1,175 @@
+use super::size_tree::SizeTree;
+use super::{Level2Entry, Level2Snapshot, Level2Update, Message};
+use crate::error::Error;
+use crate::private_client::OrderSide;
+use crate::{book_decimal, FixedDecimal};
+use std::collections::BTreeMap;
+
+/// `LocalOrderBook` is a level 2 order book kept in sync with the `level2` channel
+/// <br>
+/// Create it from the `snapshot` message and apply every following `l2update` message.
+/// <br>
+/// Best bid and ask and the size of a price band are found in logarithmic time. Depth queries take logarithmic time plus the number of levels they return.
+/// <br>
+/// ~~~~ignore
+/// let mut feed = WebsocketFeed::connect().await.unwrap();
+/// feed.subscribe(&["BTC-USD"], &[Channel::Level2]).await.unwrap();
+/// let mut book = None;
+/// while let Some(message) = feed.next().await {
+///     match message.unwrap() {
+///         Message::Snapshot(snapshot) => book = Some(LocalOrderBook::from_snapshot(&snapshot).unwrap()),
+///         Message::Level2Update(update) => book.as_mut().unwrap().apply_update(&update).unwrap(),
+///         _ => {}
+///     }
+/// }
+/// ~~~~
+#[derive(Clone, Debug)]
+pub struct LocalOrderBook {
+    product_id: String,
+    bids: Levels,
+    asks: Levels,
+}
+
+// the price levels of one side of the book, with their sizes also kept in a `SizeTree` to sum price bands
+#[derive(Clone, Debug, Default)]
+struct Levels {
+    sizes: BTreeMap<FixedDecimal, FixedDecimal>,
+    totals: SizeTree,
+}
+
+impl LocalOrderBook {
+    /// Creates a `LocalOrderBook` from a `snapshot` message
+    pub fn from_snapshot(snapshot: &Level2Snapshot) -> Result<Self, Error> {
+        Ok(Self {
+            product_id: snapshot.product_id.clone(),
+            bids: levels(&snapshot.bids)?,
+            asks: levels(&snapshot.asks)?,
+        })
+    }
+
+    /// Applies the changes of an `l2update` message
+    /// <br>
+    /// A change with a size of zero removes the price level
+    pub fn apply_update(&mut self, update: &Level2Update) -> Result<(), Error> {
+        for change in &update.changes {
+            let price = book_decimal(&change.price)?;
+            let size = book_decimal(&change.size)?;
+            let levels = match change.side {
+                OrderSide::Buy => &mut self.bids,
+                OrderSide::Sell => &mut self.asks,
+                OrderSide::Unknown(_) => continue,
+            };
+            levels.set(price, size);
+        }
+        Ok(())
+    }
+
+    /// Applies a `snapshot` or `l2update` message for the book's product, other messages are ignored
+    /// <br>
+    /// Returns `true` if the book was changed
+    pub fn apply(&mut self, message: &Message) -> Result<bool, Error> {
+        match message {
+            Message::Snapshot(snapshot) if snapshot.product_id == self.product_id => {
+                *self = Self::from_snapshot(snapshot)?;
+                Ok(true)
+            }
+            Message::Level2Update(update) if update.product_id == self.product_id => {
+                self.apply_update(update)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// The product of the order book
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    /// The highest bid
+    pub fn best_bid(&self) -> Option<BookLevel> {
+        self.bids.sizes.iter().next_back().map(BookLevel::from)
+    }
+
+    /// The lowest ask
+    pub fn best_ask(&self) -> Option<BookLevel> {
+        self.asks.sizes.iter().next().map(BookLevel::from)
+    }
+
+    /// The difference between the lowest ask and the highest bid
+    pub fn spread(&self) -> Option<FixedDecimal> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    /// The best `depth` bids, highest price first
+    pub fn bids(&self, depth: usize) -> Vec<BookLevel> {
+        self.bids
+            .sizes
+            .iter()
+            .rev()
+            .take(depth)
+            .map(BookLevel::from)
+            .collect()
+    }
+
+    /// The best `depth` asks, lowest price first
+    pub fn asks(&self, depth: usize) -> Vec<BookLevel> {
+        self.asks
+            .sizes
+            .iter()
+            .take(depth)
+            .map(BookLevel::from)
+            .collect()
+    }
+
+    /// Total size of the price levels on `side` priced from `low` to `high` inclusive
+    pub fn size_between(
+        &self,
+        side: OrderSide,
+        low: FixedDecimal,
+        high: FixedDecimal,
+    ) -> FixedDecimal {
+        let levels = match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
+            OrderSide::Unknown(_) => return FixedDecimal::default(),
+        };
+        levels.totals.sum_between(&low, &high)
+    }
+}
+
+/// A structure that represents a price level of a `LocalOrderBook`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookLevel {
+    pub price: FixedDecimal,
+    pub size: FixedDecimal,
+}
+
+impl From<(&FixedDecimal, &FixedDecimal)> for BookLevel {
+    fn from((price, size): (&FixedDecimal, &FixedDecimal)) -> Self {
+        Self {
+            price: *price,
+            size: *size,
+        }
+    }
+}
+
+impl Levels {
+    // sets the size of the level at `price`, a size of zero removes the level
+    fn set(&mut self, price: FixedDecimal, size: FixedDecimal) {
+        if size.is_zero() {
+            self.sizes.remove(&price);
+        } else {
+            self.sizes.insert(price, size);
+        }
+        self.totals.set(price, size);
+    }
+}
+
+fn levels(entries: &[Level2Entry]) -> Result<Levels, Error> {
+    let mut levels = Levels::default();
+    for entry in entries {
+        levels.set(book_decimal(&entry.price)?, book_decimal(&entry.size)?);
+    }
+    Ok(levels)
+}
@@ -0,0 +1,134 @@
+use crate::FixedDecimal;
+
+// the price levels of one side of a book in a treap whose nodes hold the total size of their subtree,
+// so that the size of a price band is summed in logarithmic time
+#[derive(Clone, Debug)]
+pub(crate) struct SizeTree {
+    root: Option<Box<Node>>,
+    // state of the xorshift generator of the node priorities
+    seed: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    price: FixedDecimal,
+    size: FixedDecimal,
+    total: FixedDecimal,
+    priority: u64,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl Default for SizeTree {
+    fn default() -> Self {
+        Self {
+            root: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl SizeTree {
+    // sets the size of the level at `price`, a size of zero removes the level
+    pub(crate) fn set(&mut self, price: FixedDecimal, size: FixedDecimal) {
+        let (lower, rest) = split(self.root.take(), &price, false);
+        let (_, higher) = split(rest, &price, true);
+        let level = if size.is_zero() {
+            None
+        } else {
+            Some(Box::new(Node {
+                price,
+                size,
+                total: size,
+                priority: self.next_priority(),
+                left: None,
+                right: None,
+            }))
+        };
+        self.root = merge(merge(lower, level), higher);
+    }
+
+    // total size of the levels priced from `low` to `high` inclusive
+    pub(crate) fn sum_between(&self, low: &FixedDecimal, high: &FixedDecimal) -> FixedDecimal {
+        if low > high {
+            return FixedDecimal::default();
+        }
+        sum_below(&self.root, high, true) - sum_below(&self.root, low, false)
+    }
+
+    fn next_priority(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl Node {
+    fn update(&mut self) {
+        self.total = total(&self.left) + self.size + total(&self.right);
+    }
+
+    // whether the level is priced below `price`, or at it if `inclusive`
+    fn below(&self, price: &FixedDecimal, inclusive: bool) -> bool {
+        self.price < *price || inclusive && self.price == *price
+    }
+}
+
+fn total(node: &Option<Box<Node>>) -> FixedDecimal {
+    node.as_ref().map(|node| node.total).unwrap_or_default()
+}
+
+// splits `node` into the levels priced below `price`, or at it if `inclusive`, and the others
+fn split(
+    node: Option<Box<Node>>,
+    price: &FixedDecimal,
+    inclusive: bool,
+) -> (Option<Box<Node>>, Option<Box<Node>>) {
+    match node {
+        None => (None, None),
+        Some(mut node) if node.below(price, inclusive) => {
+            let (lower, higher) = split(node.right.take(), price, inclusive);
+            node.right = lower;
+            node.update();
+            (Some(node), higher)
+        }
+        Some(mut node) => {
+            let (lower, higher) = split(node.left.take(), price, inclusive);
+            node.left = higher;
+            node.update();
+            (lower, Some(node))
+        }
+    }
+}
+
+// joins two treaps, every level of `lower` priced below the levels of `higher`
+fn merge(lower: Option<Box<Node>>, higher: Option<Box<Node>>) -> Option<Box<Node>> {
+    match (lower, higher) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut lower), Some(higher)) if lower.priority > higher.priority => {
+            lower.right = merge(lower.right.take(), Some(higher));
+            lower.update();
+            Some(lower)
+        }
+        (Some(lower), Some(mut higher)) => {
+            higher.left = merge(Some(lower), higher.left.take());
+            higher.update();
+            Some(higher)
+        }
+    }
+}
+
+// total size of the levels priced below `price`, or at it if `inclusive`
+fn sum_below(mut node: &Option<Box<Node>>, price: &FixedDecimal, inclusive: bool) -> FixedDecimal {
+    let mut sum = FixedDecimal::default();
+    while let Some(current) = node {
+        if current.below(price, inclusive) {
+            sum = sum + total(&current.left) + current.size;
+            node = &current.right;
+        } else {
+            node = &current.left;
+        }
+    }
+    sum
+}
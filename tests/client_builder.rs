@@ -0,0 +1,146 @@
+use crate::fake_transport::{SECRET, TIME};
+use coinbase_client::client_builder::ClientBuilder;
+use coinbase_client::error::ErrorKind;
+use coinbase_client::transport::HeaderMap;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+// answers each request with `body` after `delay` and forwards the request head, lowercased
+async fn http_server(
+    body: &'static str,
+    delay: Duration,
+) -> (String, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                let mut buf = [0; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = sender.send(String::from_utf8_lossy(&head).to_lowercase());
+                tokio::time::sleep(delay).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    (url, receiver)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_base_url() {
+    let (url, mut requests) = http_server(TIME, Duration::from_millis(0)).await;
+    let client = ClientBuilder::new()
+        .base_url(&format!("{}/", url))
+        .build_public()
+        .unwrap();
+    let time = client.get_time().await.unwrap();
+    assert_eq!(time.iso.timestamp(), 1420674445);
+    let head = requests.recv().await.unwrap();
+    assert!(head.starts_with("get /time http/1.1\r\n"));
+    assert!(head.contains("user-agent: coinbase-client\r\n"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_user_agent_and_default_headers() {
+    let (url, mut requests) = http_server(TIME, Duration::from_millis(0)).await;
+    let mut headers = HeaderMap::new();
+    headers.insert("x-egress-token", "abc123".parse().unwrap());
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .user_agent("trading-bot/1.0")
+        .default_headers(headers)
+        .build_public()
+        .unwrap();
+    client.get_time().await.unwrap();
+    let head = requests.recv().await.unwrap();
+    assert!(head.contains("user-agent: trading-bot/1.0\r\n"));
+    assert!(head.contains("x-egress-token: abc123\r\n"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_user_agent_overrides_default_headers() {
+    let (url, mut requests) = http_server(TIME, Duration::from_millis(0)).await;
+    let mut headers = HeaderMap::new();
+    headers.insert("user-agent", "from-headers/0.1".parse().unwrap());
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .user_agent("trading-bot/1.0")
+        .default_headers(headers.clone())
+        .build_public()
+        .unwrap();
+    client.get_time().await.unwrap();
+    let head = requests.recv().await.unwrap();
+    assert!(head.contains("user-agent: trading-bot/1.0\r\n"));
+    assert!(!head.contains("from-headers"));
+
+    // without `user_agent` the default headers replace the crate's user agent
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .default_headers(headers)
+        .build_public()
+        .unwrap();
+    client.get_time().await.unwrap();
+    let head = requests.recv().await.unwrap();
+    assert!(head.contains("user-agent: from-headers/0.1\r\n"));
+    assert!(!head.contains("user-agent: coinbase-client"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_invalid_user_agent() {
+    let result = ClientBuilder::new().user_agent("bot\n").build_public();
+    match result {
+        Err(err) => assert!(matches!(err.kind, ErrorKind::InvalidHeader(_))),
+        Ok(_) => panic!("expected an invalid header error"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_timeout() {
+    let (url, _requests) = http_server(TIME, Duration::from_secs(5)).await;
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .connect_timeout(Duration::from_secs(1))
+        .timeout(Duration::from_millis(100))
+        .build_public()
+        .unwrap();
+    let err = client.get_time().await.unwrap_err();
+    match err.kind {
+        ErrorKind::HTTP(err) => assert!(err.is_timeout()),
+        _ => panic!("expected a timeout, got {}", err),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_reqwest_client() {
+    let (url, mut requests) = http_server("[]", Duration::from_millis(0)).await;
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .reqwest_client(reqwest::Client::new())
+        .build_private(
+            SECRET.to_string(),
+            "t9riylyad0r".to_string(),
+            "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+        )
+        .unwrap();
+    let accounts = client.get_accounts().await.unwrap();
+    assert!(accounts.is_empty());
+    let head = requests.recv().await.unwrap();
+    assert!(head.starts_with("get /accounts http/1.1\r\n"));
+    assert!(head.contains("cb-access-key: 4a9f6de8bcdee641a0a207613dfb43ef\r\n"));
+    assert!(head.contains("user-agent: coinbase-client\r\n"));
+}
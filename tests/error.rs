@@ -0,0 +1,186 @@
+use crate::fake_transport::{private_client, FakeTransport};
+use coinbase_client::error::{Error, ErrorKind};
+use coinbase_client::private_client::*;
+use coinbase_client::public_client::PublicClient;
+use coinbase_client::transport::Method;
+
+async fn place_order_error(status: u16, message: &str) -> Error {
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        status,
+        &format!(r#"{{"message":"{}"}}"#, message),
+    );
+    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "0.000001").build();
+    private_client(&transport)
+        .place_order(order)
+        .await
+        .unwrap_err()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_request_context() {
+    let transport = FakeTransport::new().respond_with_headers(
+        "GET",
+        "/products/FOO-BAR",
+        404,
+        &[("cb-request-id", "b2f3f8e4-8c1a-4e6b-9b9a-0d4c1d0a8f21")],
+        r#"{"message":"NotFound"}"#,
+    );
+    let error = PublicClient::with_transport(transport)
+        .get_product("FOO-BAR")
+        .await
+        .unwrap_err();
+    let context = error.context.as_ref().unwrap();
+    assert_eq!(context.method, Method::GET);
+    assert_eq!(context.path, "/products/FOO-BAR");
+    assert_eq!(context.status, Some(404));
+    assert_eq!(context.body.as_deref(), Some(r#"{"message":"NotFound"}"#));
+    assert_eq!(
+        context.request_id.as_deref(),
+        Some("b2f3f8e4-8c1a-4e6b-9b9a-0d4c1d0a8f21")
+    );
+    assert_eq!(
+        error.to_string(),
+        "status code: 404, message: NotFound (GET /products/FOO-BAR, request id: b2f3f8e4-8c1a-4e6b-9b9a-0d4c1d0a8f21)"
+    );
+    assert!(error.is_not_found());
+    assert!(!error.is_retryable());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_json_error_context() {
+    let transport = FakeTransport::new().respond("GET", "/accounts", 200, r#"{"unexpected":1}"#);
+    let error = private_client(&transport).get_accounts().await.unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::JSON(_)));
+    let context = error.context.as_ref().unwrap();
+    assert_eq!(context.path, "/accounts");
+    assert_eq!(context.status, Some(200));
+    assert_eq!(context.body.as_deref(), Some(r#"{"unexpected":1}"#));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_classification() {
+    let transport = FakeTransport::new()
+        .respond("GET", "/accounts", 401, r#"{"message":"Invalid API Key"}"#)
+        .respond(
+            "GET",
+            "/accounts",
+            429,
+            r#"{"message":"Rate limit exceeded"}"#,
+        )
+        .respond(
+            "GET",
+            "/accounts",
+            503,
+            r#"{"message":"Service Unavailable"}"#,
+        );
+    let client = private_client(&transport);
+
+    let error = client.get_accounts().await.unwrap_err();
+    assert!(error.is_auth());
+    assert!(!error.is_retryable());
+    assert_eq!(error.status(), Some(401));
+
+    let error = client.get_accounts().await.unwrap_err();
+    assert!(error.is_rate_limit());
+    assert!(error.is_retryable());
+
+    let error = client.get_accounts().await.unwrap_err();
+    assert!(error.is_retryable());
+    assert!(!error.is_rate_limit());
+    assert!(matches!(error.kind, ErrorKind::Status(_)));
+}
+
+#[test]
+fn test_retryable_websocket_errors() {
+    use std::io;
+    use tokio_tungstenite::tungstenite::Error as WebsocketError;
+
+    assert!(Error::from(WebsocketError::ConnectionClosed).is_retryable());
+    let reset = io::Error::new(io::ErrorKind::ConnectionReset, "reset");
+    assert!(Error::from(WebsocketError::Io(reset)).is_retryable());
+
+    let denied = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+    assert!(!Error::from(WebsocketError::Io(denied)).is_retryable());
+    assert!(!Error::from(WebsocketError::AlreadyClosed).is_retryable());
+    assert!(!Error::from(WebsocketError::Utf8).is_retryable());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_known_messages() {
+    let error = place_order_error(400, "Insufficient funds").await;
+    assert!(matches!(error.kind, ErrorKind::InsufficientFunds(_)));
+    assert!(error.is_validation());
+
+    let error = place_order_error(400, "Order size is too small. Minimum size is 0.001").await;
+    assert!(matches!(error.kind, ErrorKind::OrderSizeTooSmall(_)));
+    assert!(error.is_validation());
+
+    let error = place_order_error(400, "Post only mode").await;
+    match error.kind {
+        ErrorKind::PostOnly(status) => {
+            assert_eq!(status.code, 400);
+            assert_eq!(status.message, "Post only mode");
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+
+    let error = place_order_error(400, "Invalid order_type").await;
+    assert!(matches!(error.kind, ErrorKind::Status(_)));
+    assert!(error.is_validation());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_html_error_body() {
+    let page = "<html>\r\n<head><title>502 Bad Gateway</title></head>\r\n<body><center><h1>502 Bad Gateway</h1></center><hr><center>cloudflare</center></body>\r\n</html>\r\n";
+    let transport = FakeTransport::new().respond("GET", "/time", 502, page);
+    let error = PublicClient::with_transport(transport)
+        .get_time()
+        .await
+        .unwrap_err();
+    match &error.kind {
+        ErrorKind::Status(status) => {
+            assert_eq!(status.code, 502);
+            assert!(status.message.contains("cloudflare"));
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+    assert_eq!(error.context.unwrap().body.as_deref(), Some(page));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_empty_error_body() {
+    let transport = FakeTransport::new().respond("GET", "/orders/missing", 404, "");
+    let error = private_client(&transport)
+        .get_order("missing")
+        .await
+        .unwrap_err();
+    assert!(error.is_not_found());
+    assert_eq!(
+        error.to_string(),
+        "status code: 404, message: Not Found (GET /orders/missing)"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_profile_transfer_text_error() {
+    let transport =
+        FakeTransport::new().respond("POST", "/profiles/transfer", 400, "Insufficient funds\n");
+    let error = private_client(&transport)
+        .create_profile_transfer(
+            "e1d7731f-b7e2-4285-b711-eeec76fc2aff",
+            "3510ac37-1a99-4c9c-9865-15f1bc5a832e",
+            "USD",
+            "10.00",
+        )
+        .await
+        .unwrap_err();
+    match error.kind {
+        ErrorKind::InsufficientFunds(status) => {
+            assert_eq!(status.code, 400);
+            assert_eq!(status.message, "Insufficient funds");
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
@@ -0,0 +1,120 @@
+use coinbase_client::error::Error;
+use coinbase_client::private_client::PrivateClient;
+use coinbase_client::transport::*;
+use futures::future::{BoxFuture, FutureExt};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+pub const SECRET: &str =
+    "tGJSu7SuV3/HOR1/9DcFwO1s560BKI51SDEbnwuvTPbw4BbG5lYJLuKUFpD8TPU61R85dxJpGTygKZ5v+6wJdA==";
+
+pub const ORDER: &str = r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2","price":"36000.00000000","size":"1.00000000","product_id":"BTC-USD","side":"buy","stp":"dc","type":"limit","time_in_force":"GTC","post_only":false,"created_at":"2021-06-19T20:24:20.467086Z","fill_fees":"0.0000000000000000","filled_size":"0.00000000","executed_value":"0.0000000000000000","status":"open","settled":false}"#;
+
+pub const PRODUCT: &str = r#"{"id":"BTC-USD","display_name":"BTC/USD","base_currency":"BTC","quote_currency":"USD","base_increment":"0.00000001","quote_increment":"0.01000000","base_min_size":"0.00100000","base_max_size":"280.00000000","min_market_funds":"5","max_market_funds":"1000000","status":"online","status_message":"","cancel_only":false,"limit_only":false,"post_only":false,"trading_disabled":false}"#;
+
+pub const TIME: &str = r#"{"iso":"2015-01-07T23:47:25.201Z","epoch":1420674445.201}"#;
+
+// a private client with test credentials that sends its requests to `transport`
+pub fn private_client(transport: &FakeTransport) -> PrivateClient {
+    PrivateClient::with_transport(
+        SECRET.to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+        transport.clone(),
+    )
+}
+
+// in-memory transport that answers requests with canned responses and records them
+#[derive(Clone, Default)]
+pub struct FakeTransport {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    routes: Vec<Route>,
+    requests: Vec<Request>,
+}
+
+struct Route {
+    method: Method,
+    path: String,
+    // the last response is repeated once the others are used up
+    responses: VecDeque<Response>,
+}
+
+impl FakeTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // answers `method` requests to `path`, including the query string, with `status` and `body`
+    pub fn respond(self, method: &str, path: &str, status: u16, body: &str) -> Self {
+        self.respond_with_headers(method, path, status, &[], body)
+    }
+
+    pub fn respond_with_headers(
+        self,
+        method: &str,
+        path: &str,
+        status: u16,
+        headers: &[(&'static str, &str)],
+        body: &str,
+    ) -> Self {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(*name, value.parse().unwrap());
+        }
+        let response = Response {
+            status,
+            headers: header_map,
+            body: body.to_string(),
+        };
+        let method: Method = method.parse().unwrap();
+        {
+            let mut state = self.state.lock().unwrap();
+            match state
+                .routes
+                .iter_mut()
+                .find(|route| route.method == method && route.path == path)
+            {
+                Some(route) => route.responses.push_back(response),
+                None => state.routes.push(Route {
+                    method,
+                    path: path.to_string(),
+                    responses: vec![response].into(),
+                }),
+            }
+        }
+        self
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn last_request(&self) -> Request {
+        self.requests().pop().expect("no request was sent")
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+        let mut state = self.state.lock().unwrap();
+        let response = match state
+            .routes
+            .iter_mut()
+            .find(|route| route.method == request.method && route.path == request.path)
+        {
+            Some(route) if route.responses.len() > 1 => route.responses.pop_front().unwrap(),
+            Some(route) => route.responses[0].clone(),
+            None => Response {
+                status: 404,
+                headers: HeaderMap::new(),
+                body: r#"{"message":"NotFound"}"#.to_string(),
+            },
+        };
+        state.requests.push(request);
+        async move { Ok(response) }.boxed()
+    }
+}
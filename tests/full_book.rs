@@ -0,0 +1,207 @@
+use crate::order_book::decimal;
+use coinbase_client::private_client::OrderSide;
+use coinbase_client::public_client::{FullBookEntry, OrderBook};
+use coinbase_client::websocket::*;
+
+const SNAPSHOT: &str = r#"{"sequence":100,"bids":[["295.96","4.39088265","da863862-25f4-4868-ac41-005d11ab0a5f"],["295.96","1.0","7b0f1225-0a43-4d7c-b8e8-4ab4ec1e2a8b"],["295.90","2.0","1ec2cb1f-1c2f-4ff7-8a0d-f5b2be3c3f21"]],"asks":[["296.12","0.5","4dbf7a7c-e1a6-4b2a-9cfb-5d1d3b2f8a36"]]}"#;
+
+fn snapshot() -> OrderBook<FullBookEntry> {
+    serde_json::from_str(SNAPSHOT).unwrap()
+}
+
+fn open(sequence: u64, order_id: &str, side: &str, price: &str, size: &str) -> Message {
+    message(&format!(
+        r#"{{"type":"open","time":"2021-06-21T17:05:49.360000Z","product_id":"BTC-USD","sequence":{},"order_id":"{}","price":"{}","remaining_size":"{}","side":"{}"}}"#,
+        sequence, order_id, price, size, side
+    ))
+}
+
+fn done(sequence: u64, order_id: &str, side: &str) -> Message {
+    message(&format!(
+        r#"{{"type":"done","time":"2021-06-21T17:05:49.380000Z","product_id":"BTC-USD","sequence":{},"price":"295.96","order_id":"{}","reason":"canceled","side":"{}","remaining_size":"0"}}"#,
+        sequence, order_id, side
+    ))
+}
+
+fn match_message(sequence: u64, maker_order_id: &str, size: &str) -> Message {
+    message(&format!(
+        r#"{{"type":"match","trade_id":10,"sequence":{},"maker_order_id":"{}","taker_order_id":"132fb6ae-456b-4654-b4e0-d681ac05cea1","time":"2021-06-21T17:05:49.370000Z","product_id":"BTC-USD","size":"{}","price":"295.96","side":"buy"}}"#,
+        sequence, maker_order_id, size
+    ))
+}
+
+fn message(json: &str) -> Message {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_buffer_until_snapshot() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    // at or below the snapshot sequence, discarded when the snapshot is loaded
+    let stale = open(
+        100,
+        "a5e1b7c1-0d8a-4c3f-8f9b-3f5a0c6a9e11",
+        "buy",
+        "290.00",
+        "9",
+    );
+    assert_eq!(book.apply(&stale).unwrap(), BookEvent::Buffered);
+    let fresh = open(
+        101,
+        "b9f0f6a2-4c55-4a63-bc0e-7c2b3f0a1d22",
+        "sell",
+        "296.00",
+        "1.5",
+    );
+    assert_eq!(book.apply(&fresh).unwrap(), BookEvent::Buffered);
+    assert_eq!(book.sequence(), None);
+
+    assert_eq!(book.load_snapshot(&snapshot()).unwrap(), BookEvent::Applied);
+    assert_eq!(book.sequence(), Some(101));
+    assert_eq!(book.order_count(), 5);
+    assert!(book.order("a5e1b7c1-0d8a-4c3f-8f9b-3f5a0c6a9e11").is_none());
+    assert_eq!(
+        book.best_ask(),
+        Some(BookLevel {
+            price: decimal("296.00"),
+            size: decimal("1.5")
+        })
+    );
+    assert_eq!(
+        book.best_bid(),
+        Some(BookLevel {
+            price: decimal("295.96"),
+            size: decimal("5.39088265")
+        })
+    );
+}
+
+#[test]
+fn test_apply() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    book.load_snapshot(&snapshot()).unwrap();
+
+    let maker = "da863862-25f4-4868-ac41-005d11ab0a5f";
+    assert_eq!(
+        book.apply(&match_message(101, maker, "0.39088265"))
+            .unwrap(),
+        BookEvent::Applied
+    );
+    assert_eq!(book.order(maker).unwrap().size, decimal("4.0"));
+
+    let change = message(
+        r#"{"type":"change","time":"2021-06-21T17:05:49.390000Z","sequence":102,"order_id":"7b0f1225-0a43-4d7c-b8e8-4ab4ec1e2a8b","product_id":"BTC-USD","new_size":"0.5","old_size":"1.0","price":"295.96","side":"buy"}"#,
+    );
+    assert_eq!(book.apply(&change).unwrap(), BookEvent::Applied);
+    assert_eq!(book.best_bid().unwrap().size, decimal("4.5"));
+
+    assert_eq!(
+        book.apply(&done(103, maker, "buy")).unwrap(),
+        BookEvent::Applied
+    );
+    let orders = book.orders_at(OrderSide::Buy, decimal("295.96"));
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].order_id, "7b0f1225-0a43-4d7c-b8e8-4ab4ec1e2a8b");
+
+    assert_eq!(
+        book.apply(&done(104, "7b0f1225-0a43-4d7c-b8e8-4ab4ec1e2a8b", "buy"))
+            .unwrap(),
+        BookEvent::Applied
+    );
+    assert_eq!(book.best_bid().unwrap().price, decimal("295.90"));
+    assert_eq!(book.bids(10).len(), 1);
+    assert_eq!(book.sequence(), Some(104));
+}
+
+#[test]
+fn test_ignored_messages() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    book.load_snapshot(&snapshot()).unwrap();
+    let heartbeat = message(
+        r#"{"type":"heartbeat","last_trade_id":20153558,"product_id":"BTC-USD","sequence":101,"time":"2021-06-21T17:05:49.250000Z"}"#,
+    );
+    assert_eq!(book.apply(&heartbeat).unwrap(), BookEvent::Ignored);
+    let other_product = message(
+        r#"{"type":"open","time":"2021-06-21T17:05:49.360000Z","product_id":"ETH-USD","sequence":7,"order_id":"b9f0f6a2-4c55-4a63-bc0e-7c2b3f0a1d22","price":"1.00","remaining_size":"1","side":"buy"}"#,
+    );
+    assert_eq!(book.apply(&other_product).unwrap(), BookEvent::Ignored);
+    assert_eq!(
+        book.apply(&done(99, "da863862-25f4-4868-ac41-005d11ab0a5f", "buy"))
+            .unwrap(),
+        BookEvent::Ignored
+    );
+    assert_eq!(book.order_count(), 4);
+}
+
+#[test]
+fn test_sequence_gap() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    book.load_snapshot(&snapshot()).unwrap();
+    let gap = done(105, "da863862-25f4-4868-ac41-005d11ab0a5f", "buy");
+    assert_eq!(
+        book.apply(&gap).unwrap(),
+        BookEvent::Resync {
+            expected: 101,
+            received: 105
+        }
+    );
+    assert_eq!(book.sequence(), None);
+    assert_eq!(
+        book.apply(&open(
+            106,
+            "b9f0f6a2-4c55-4a63-bc0e-7c2b3f0a1d22",
+            "sell",
+            "296.00",
+            "1"
+        ))
+        .unwrap(),
+        BookEvent::Buffered
+    );
+}
+
+#[test]
+fn test_sequence_gap_in_buffer() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    book.apply(&open(
+        103,
+        "b9f0f6a2-4c55-4a63-bc0e-7c2b3f0a1d22",
+        "sell",
+        "296.00",
+        "1",
+    ))
+    .unwrap();
+    assert_eq!(
+        book.load_snapshot(&snapshot()).unwrap(),
+        BookEvent::Resync {
+            expected: 101,
+            received: 103
+        }
+    );
+    assert_eq!(book.sequence(), None);
+}
+
+#[test]
+fn test_duplicate_open() {
+    let mut book = FullOrderBook::new("BTC-USD");
+    book.load_snapshot(&snapshot()).unwrap();
+    let first = "da863862-25f4-4868-ac41-005d11ab0a5f";
+
+    // an order of the snapshot opened again keeps its place in the level
+    book.apply(&open(101, first, "buy", "295.96", "4.39088265"))
+        .unwrap();
+    assert_eq!(book.order_count(), 4);
+    let orders = book.orders_at(OrderSide::Buy, decimal("295.96"));
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].order_id, first);
+    assert_eq!(book.best_bid().unwrap().size, decimal("5.39088265"));
+
+    // at another price it is moved
+    book.apply(&open(102, first, "buy", "295.90", "1.0"))
+        .unwrap();
+    assert_eq!(book.orders_at(OrderSide::Buy, decimal("295.96")).len(), 1);
+    assert_eq!(book.orders_at(OrderSide::Buy, decimal("295.90")).len(), 2);
+
+    book.apply(&done(103, first, "buy")).unwrap();
+    assert_eq!(book.order_count(), 3);
+    assert_eq!(book.bids(10)[1].size, decimal("2.0"));
+}
@@ -1,2 +1,15 @@
+mod client_builder;
+mod error;
+mod fake_transport;
+mod full_book;
+#[cfg(feature = "oracle")]
+mod oracle;
+mod order_book;
+mod order_registry;
 mod private_client;
-mod public_client;
\ No newline at end of file
+mod public_client;
+mod rate_limit;
+mod retry;
+mod trading_rules;
+mod validation;
+mod websocket;
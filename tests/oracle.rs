@@ -0,0 +1,131 @@
+use crate::fake_transport::{private_client, FakeTransport};
+use coinbase_client::error::ErrorKind;
+use coinbase_client::private_client::*;
+use tiny_keccak::Hasher;
+
+const SECRET_KEY: [u8; 32] = [7; 32];
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = tiny_keccak::Keccak::v256();
+    let mut hash = [0; 32];
+    keccak.update(data);
+    keccak.finalize(&mut hash);
+    hash
+}
+
+fn word(value: u64) -> Vec<u8> {
+    let mut word = vec![0; 24];
+    word.extend_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut encoded = word(value.len() as u64);
+    encoded.extend_from_slice(value.as_bytes());
+    encoded.resize(64, 0);
+    encoded
+}
+
+// the ABI encoding of ("prices", timestamp, key, value)
+fn message(timestamp: u64, key: &str, value: u64) -> Vec<u8> {
+    let mut message = word(0x80);
+    message.extend(word(timestamp));
+    message.extend(word(0xc0));
+    message.extend(word(value));
+    message.extend(string("prices"));
+    message.extend(string(key));
+    message
+}
+
+fn sign(message: &[u8], secret_key: &[u8; 32]) -> Vec<u8> {
+    let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+    prefixed.extend_from_slice(&keccak256(message));
+    let secret_key = libsecp256k1::SecretKey::parse(secret_key).unwrap();
+    let (signature, recovery_id) = libsecp256k1::sign(
+        &libsecp256k1::Message::parse(&keccak256(&prefixed)),
+        &secret_key,
+    );
+    let mut encoded = signature.serialize().to_vec();
+    encoded.extend(word(recovery_id.serialize() as u64 + 27));
+    encoded
+}
+
+fn address(secret_key: &[u8; 32]) -> String {
+    let secret_key = libsecp256k1::SecretKey::parse(secret_key).unwrap();
+    let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+    format!(
+        "0x{}",
+        hex::encode(&keccak256(&public_key.serialize()[1..])[12..])
+    )
+}
+
+async fn oracle(messages: &[Vec<u8>], signatures: &[Vec<u8>]) -> OracleData {
+    let body = serde_json::json!({
+        "timestamp": "1583195060",
+        "messages": messages.iter().map(|m| format!("0x{}", hex::encode(m))).collect::<Vec<_>>(),
+        "signatures": signatures.iter().map(|s| format!("0x{}", hex::encode(s))).collect::<Vec<_>>(),
+        "prices": {"BTC": "8846.945", "ETH": "226.535"},
+    });
+    let transport = FakeTransport::new().respond("GET", "/oracle", 200, &body.to_string());
+    private_client(&transport).oracle().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_verify_oracle() {
+    let messages = [
+        message(1583195060, "BTC", 8846945000),
+        message(1583195060, "ETH", 226535000),
+    ];
+    let signatures: Vec<_> = messages.iter().map(|m| sign(m, &SECRET_KEY)).collect();
+    let prices = oracle(&messages, &signatures)
+        .await
+        .verify_reporter(&address(&SECRET_KEY))
+        .unwrap();
+    assert_eq!(
+        prices[0],
+        OraclePrice {
+            kind: "prices".to_string(),
+            timestamp: 1583195060,
+            key: "BTC".to_string(),
+            value: 8846945000,
+        }
+    );
+    assert_eq!(prices[1].key, "ETH");
+    assert_eq!(prices[1].value, 226535000);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_verify_oracle_wrong_reporter() {
+    let messages = [message(1583195060, "BTC", 8846945000)];
+    let signatures = [sign(&messages[0], &SECRET_KEY)];
+    let data = oracle(&messages, &signatures).await;
+    let error = data.verify().unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Oracle(_)));
+    assert!(data.verify_reporter(&address(&[8; 32])).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_verify_oracle_tampered_message() {
+    let signatures = [sign(&message(1583195060, "BTC", 8846945000), &SECRET_KEY)];
+    // the price was changed after signing
+    let messages = [message(1583195060, "BTC", 1)];
+    let error = oracle(&messages, &signatures)
+        .await
+        .verify_reporter(&address(&SECRET_KEY))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "oracle error: message is not signed by the reporter"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_verify_oracle_invalid_message() {
+    let messages = [message(1583195060, "BTC", 8846945000)[..100].to_vec()];
+    let signatures = [sign(&messages[0], &SECRET_KEY)];
+    let error = oracle(&messages, &signatures)
+        .await
+        .verify_reporter(&address(&SECRET_KEY))
+        .unwrap_err();
+    assert_eq!(error.to_string(), "oracle error: invalid message");
+}
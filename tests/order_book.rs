@@ -0,0 +1,217 @@
+use coinbase_client::error::{ErrorKind, ValidationError};
+use coinbase_client::private_client::OrderSide;
+use coinbase_client::websocket::*;
+use coinbase_client::FixedDecimal;
+
+const SNAPSHOT: &str = r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["10101.10","0.45"],["10100.00","1.5"],["10099.95","2"]],"asks":[["10102.55","0.57"],["10103.00","3"],["10110.00","1"]]}"#;
+
+pub fn decimal(value: &str) -> FixedDecimal {
+    value.parse().unwrap()
+}
+
+fn book() -> LocalOrderBook {
+    match serde_json::from_str(SNAPSHOT).unwrap() {
+        Message::Snapshot(snapshot) => LocalOrderBook::from_snapshot(&snapshot).unwrap(),
+        message => panic!("unexpected message {:?}", message),
+    }
+}
+
+fn update(changes: &str) -> Message {
+    serde_json::from_str(&format!(
+        r#"{{"type":"l2update","product_id":"BTC-USD","time":"2021-06-21T17:05:49.301000Z","changes":{}}}"#,
+        changes
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_from_snapshot() {
+    let book = book();
+    assert_eq!(book.product_id(), "BTC-USD");
+    assert_eq!(
+        book.best_bid(),
+        Some(BookLevel {
+            price: decimal("10101.10"),
+            size: decimal("0.45")
+        })
+    );
+    assert_eq!(
+        book.best_ask(),
+        Some(BookLevel {
+            price: decimal("10102.55"),
+            size: decimal("0.57")
+        })
+    );
+    assert_eq!(book.spread(), Some(decimal("1.45")));
+}
+
+#[test]
+fn test_apply_update() {
+    let mut book = book();
+    let changed = book
+        .apply(&update(
+            r#"[["buy","10101.50","0.25"],["sell","10102.55","0"],["buy","10100.0","4"]]"#,
+        ))
+        .unwrap();
+    assert!(changed);
+    assert_eq!(book.best_bid().unwrap().price, decimal("10101.50"));
+    assert_eq!(book.best_ask().unwrap().price, decimal("10103.00"));
+    // "10100.0" and "10100.00" are the same price level
+    assert_eq!(
+        book.bids(3),
+        vec![
+            BookLevel {
+                price: decimal("10101.50"),
+                size: decimal("0.25")
+            },
+            BookLevel {
+                price: decimal("10101.10"),
+                size: decimal("0.45")
+            },
+            BookLevel {
+                price: decimal("10100.00"),
+                size: decimal("4.0")
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_apply_ignores_other_messages() {
+    let mut book = book();
+    let other_product = serde_json::from_str(
+        r#"{"type":"l2update","product_id":"ETH-USD","time":"2021-06-21T17:05:49.301000Z","changes":[["buy","1.00","1"]]}"#,
+    )
+    .unwrap();
+    assert!(!book.apply(&other_product).unwrap());
+    assert_eq!(book.best_bid().unwrap().price, decimal("10101.10"));
+}
+
+#[test]
+fn test_depth() {
+    let book = book();
+    assert_eq!(book.bids(2).len(), 2);
+    assert_eq!(book.asks(10).len(), 3);
+    assert_eq!(book.asks(1)[0].price, decimal("10102.55"));
+    assert_eq!(book.bids(0), vec![]);
+}
+
+#[test]
+fn test_size_between() {
+    let book = book();
+    assert_eq!(
+        book.size_between(OrderSide::Buy, decimal("10100.00"), decimal("10101.10")),
+        decimal("1.95")
+    );
+    assert_eq!(
+        book.size_between(OrderSide::Sell, decimal("10103.00"), decimal("10200.00")),
+        decimal("4.0")
+    );
+    assert_eq!(
+        book.size_between(OrderSide::Sell, decimal("10200.00"), decimal("10100.00")),
+        decimal("0.0")
+    );
+}
+
+#[test]
+fn test_invalid_price() {
+    let mut book = book();
+    assert!(book.apply(&update(r#"[["buy","abc","1"]]"#)).is_err());
+}
+
+#[test]
+fn test_exact_sizes() {
+    let mut book = book();
+    book.apply(&update(
+        r#"[["buy","10098.00","0.1"],["buy","10097.00","0.2"],["buy","10101.1","0.3"]]"#,
+    ))
+    .unwrap();
+    // 0.1 + 0.2 is 0.30000000000000004 in floats
+    assert_eq!(
+        book.size_between(OrderSide::Buy, decimal("10097"), decimal("10098")),
+        decimal("0.3")
+    );
+    // "10101.1" updates the "10101.10" level instead of adding one
+    assert_eq!(book.bids(10).len(), 5);
+    assert_eq!(book.best_bid().unwrap().size, decimal("0.3"));
+}
+
+#[test]
+fn test_size_between_after_updates() {
+    let mut book = book();
+    let mut seed = 7u64;
+    for _ in 0..500 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        // sizes of zero remove levels
+        let (price, size) = (10090 + (seed >> 33) % 20, (seed >> 20) % 4);
+        book.apply(&update(&format!(
+            r#"[["buy","{}.5","{}.25"]]"#,
+            price, size
+        )))
+        .unwrap();
+        book.apply(&update(&format!(r#"[["buy","{}.0","{}"]]"#, price, size)))
+            .unwrap();
+    }
+    let levels = book.bids(usize::MAX);
+    for (low, high) in &[
+        ("10090", "10110"),
+        ("10093.5", "10100"),
+        ("10095.1", "10095.4"),
+    ] {
+        let (low, high) = (decimal(low), decimal(high));
+        let expected = levels
+            .iter()
+            .filter(|level| level.price >= low && level.price <= high)
+            .map(|level| level.size)
+            .sum::<FixedDecimal>();
+        assert_eq!(book.size_between(OrderSide::Buy, low, high), expected);
+    }
+}
+
+#[test]
+fn test_large_sizes() {
+    let snapshot = serde_json::from_str(
+        r#"{"type":"snapshot","product_id":"SHIB-USD","bids":[["0.00002512","20000000000.00000000"]],"asks":[["0.00002513","153000000.00000000"]]}"#,
+    )
+    .unwrap();
+    let mut book = match snapshot {
+        Message::Snapshot(snapshot) => LocalOrderBook::from_snapshot(&snapshot).unwrap(),
+        message => panic!("unexpected message {:?}", message),
+    };
+    assert_eq!(book.best_bid().unwrap().size, decimal("20000000000"));
+    book.apply_update(
+        &match update(r#"[["buy","0.00002511","35000000000.00000000"]]"#) {
+            Message::Level2Update(update) => update,
+            message => panic!("unexpected message {:?}", message),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        book.size_between(OrderSide::Buy, decimal("0.00002511"), decimal("0.00002512")),
+        decimal("55000000000")
+    );
+}
+
+#[test]
+fn test_parse_invalid_decimal() {
+    let err = "1.2.3".parse::<FixedDecimal>().unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ErrorKind::Validation(ValidationError::InvalidNumber { value, .. }) if value == "1.2.3"
+    ));
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_to_decimal() {
+    use coinbase_client::ToDecimal;
+
+    assert_eq!(
+        decimal("10101.10").to_decimal().unwrap().to_string(),
+        "10101.1"
+    );
+    assert_eq!(0.1f64.to_decimal().unwrap().to_string(), "0.1");
+    assert!("abc".to_decimal().is_err());
+}
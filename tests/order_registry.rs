@@ -0,0 +1,116 @@
+use coinbase_client::private_client::*;
+use coinbase_client::websocket::Message;
+
+const ORDER: &str = r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2","price":"36000.00000000","size":"1.00000000","product_id":"BTC-USD","side":"buy","stp":"dc","type":"limit","time_in_force":"GTC","post_only":false,"created_at":"2021-06-19T20:24:20.467086Z","fill_fees":"0.0000000000000000","filled_size":"0.25000000","executed_value":"9000.0000000000000000","status":"open","settled":false}"#;
+
+const RECEIVED: &str = r#"{"type":"received","time":"2021-06-21T17:05:49.350000Z","product_id":"BTC-USD","sequence":10,"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","size":"1.34","price":"502.1","side":"buy","order_type":"limit","client_oid":"1f3a7c2e-5d4b-4e8f-9a6c-0b1d2e3f4a5b"}"#;
+
+fn placed() -> PlacedOrder {
+    PlacedOrder {
+        id: "d0c5340b-6d6c-49d9-b567-48c4bfca13d2".to_string(),
+        client_oid: "8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e".to_string(),
+    }
+}
+
+fn order_info(client_oid: Option<&str>) -> OrderInfo {
+    let mut order: serde_json::Value = serde_json::from_str(ORDER).unwrap();
+    if let Some(client_oid) = client_oid {
+        order["client_oid"] = client_oid.into();
+    }
+    serde_json::from_value(order).unwrap()
+}
+
+#[test]
+fn test_insert_and_lookup() {
+    let mut registry = OrderRegistry::new();
+    assert!(registry.is_empty());
+    registry.insert(&placed());
+    assert_eq!(registry.len(), 1);
+
+    let order = registry
+        .get("8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e")
+        .unwrap();
+    assert_eq!(order.id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    assert!(order.info.is_none());
+    assert_eq!(
+        registry.client_oid("d0c5340b-6d6c-49d9-b567-48c4bfca13d2"),
+        Some("8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e")
+    );
+    assert!(registry
+        .get_by_order_id("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .is_some());
+    assert!(registry.get_by_order_id("unknown").is_none());
+
+    let removed = registry
+        .remove("8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e")
+        .unwrap();
+    assert_eq!(removed.id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    assert!(registry.is_empty());
+    assert!(registry
+        .client_oid("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .is_none());
+}
+
+#[test]
+fn test_update() {
+    let mut registry = OrderRegistry::new();
+    // unknown orders without a client_oid are ignored
+    assert!(!registry.update(order_info(None)));
+    assert!(registry.is_empty());
+
+    registry.insert(&placed());
+    assert!(registry.update(order_info(None)));
+    let info = registry
+        .get_by_order_id("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .unwrap()
+        .info
+        .as_ref()
+        .unwrap();
+    assert_eq!(info.filled_size.to_string(), "0.25000000");
+
+    // orders placed elsewhere are inserted by their client_oid
+    let mut registry = OrderRegistry::new();
+    assert!(registry.update(order_info(Some("2b9e4f6a-7c1d-4e3b-8a5f-6d0c9b8a7e6f"))));
+    assert_eq!(
+        registry.client_oid("d0c5340b-6d6c-49d9-b567-48c4bfca13d2"),
+        Some("2b9e4f6a-7c1d-4e3b-8a5f-6d0c9b8a7e6f")
+    );
+}
+
+#[test]
+fn test_reused_client_oid() {
+    let mut registry = OrderRegistry::new();
+    registry.insert(&placed());
+    registry.update(order_info(None));
+    let replacement = PlacedOrder {
+        id: "5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39".to_string(),
+        ..placed()
+    };
+    let order = registry.insert(&replacement);
+    assert_eq!(order.id, "5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39");
+    assert!(order.info.is_none());
+    assert_eq!(registry.len(), 1);
+    assert!(registry
+        .client_oid("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .is_none());
+}
+
+#[test]
+fn test_apply_received() {
+    let mut registry = OrderRegistry::new();
+    let received: Message = serde_json::from_str(RECEIVED).unwrap();
+    assert!(registry.apply(&received));
+    assert_eq!(
+        registry.client_oid("d50ec984-77a8-460a-b958-66f114b0de9b"),
+        Some("1f3a7c2e-5d4b-4e8f-9a6c-0b1d2e3f4a5b")
+    );
+
+    let without_client_oid: Message = serde_json::from_str(&RECEIVED.replace(
+        r#","client_oid":"1f3a7c2e-5d4b-4e8f-9a6c-0b1d2e3f4a5b""#,
+        "",
+    ))
+    .unwrap();
+    assert!(!registry.apply(&without_client_oid));
+    assert!(!registry.apply(&Message::Disconnected));
+    assert_eq!(registry.len(), 1);
+}
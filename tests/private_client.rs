@@ -1,57 +1,164 @@
+use crate::fake_transport::{private_client, FakeTransport, ORDER, SECRET};
+use chrono::{TimeZone, Utc};
 use coinbase_client::private_client::*;
-use dotenv;
-use std::env;
+use coinbase_client::transport::Method;
+use coinbase_client::websocket::DoneReason;
+use crypto::mac::Mac;
+use futures::TryStreamExt;
 
-fn create_client() -> PrivateClient {
-    dotenv::from_filename(".env").expect("error reading .env file");
-    let secret = env::var("SECRET").expect("Cant find api secret");
-    let passphrase = env::var("PASSPHRASE").expect("Cant find api passphrase");
-    let key = env::var("KEY").expect("Cant find api key");
-    PrivateClient::new_sandbox(secret, passphrase, key)
+const ACCOUNT: &str = r#"{"id":"0589d87c-154d-4f5b-9ed9-ff814f70e04a","currency":"BTC","balance":"0.0000000000000000","available":"0","hold":"0.0000000000000000","profile_id":"75da88c5-05bf-4f54-bc85-5c775bd68254","trading_enabled":true}"#;
+
+const MARKET_ORDER: &str = r#"{"id":"8b99b139-58f2-4ab2-8e7a-c11c846e3022","product_id":"BTC-USD","profile_id":"75da88c5-05bf-4f54-bc85-5c775bd68254","side":"buy","funds":"99.50248756","specified_funds":"100.00000000","type":"market","post_only":false,"created_at":"2021-06-19T20:24:20.467086Z","done_at":"2021-06-19T20:24:20.49Z","done_reason":"filled","fill_fees":"0.4975124378000000","filled_size":"0.00311230","executed_value":"99.5021000300000000","status":"done","settled":true}"#;
+
+const STOP_ORDER: &str = r#"{"id":"a4a2c8a4-8fa5-4d3a-92d6-e87d3f1b2a67","price":"30000.00000000","size":"0.50000000","product_id":"BTC-USD","profile_id":"75da88c5-05bf-4f54-bc85-5c775bd68254","side":"sell","type":"limit","time_in_force":"GTC","post_only":false,"created_at":"2021-06-19T20:24:20.467086Z","fill_fees":"0.0000000000000000","filled_size":"0.00000000","executed_value":"0.0000000000000000","status":"active","settled":false,"stop":"loss","stop_price":"31000.00000000"}"#;
+
+const GTT_ORDER: &str = r#"{"id":"5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39","price":"35000.00000000","size":"0.10000000","product_id":"BTC-USD","profile_id":"75da88c5-05bf-4f54-bc85-5c775bd68254","side":"buy","type":"limit","time_in_force":"GTT","expire_time":"2021-06-19T21:24:20.467086Z","post_only":true,"created_at":"2021-06-19T20:24:20.467086Z","done_at":"2021-06-19T21:24:20.5Z","done_reason":"canceled","fill_fees":"0.0000000000000000","filled_size":"0.00000000","executed_value":"0.0000000000000000","status":"done","settled":true}"#;
+
+const FILL: &str = r#"{"trade_id":74,"product_id":"BTC-USD","price":"10.00","size":"0.01","order_id":"4f2756cf-dcb5-492b-83e5-5f2141892758","created_at":"2021-06-19T20:24:20.467086Z","liquidity":"T","fee":"0.00025","settled":true,"side":"buy"}"#;
+
+const TRANSFERS: &str = r#"[{"id":"80259339-7bf9-498f-8200-ddbd32a1c545","type":"deposit","created_at":"2021-06-18 01:37:48.078524+00","completed_at":"2021-06-18 01:37:49.756147+00","canceled_at":null,"processed_at":"2021-06-18 01:37:49.756147+00","account_id":"bf091906-ca7f-499e-95fa-5bc15e918b46","user_id":"5eeac63c90b913bf3cf7c92e","user_nonce":null,"amount":"10.00000000","details":{"coinbase_account_id":"2b760113-fbba-5600-ac74-36482c130768","coinbase_transaction_id":"5e697ed49f8417148f3366ea","coinbase_payment_method_id":""}}]"#;
+
+const WITHDRAWAL: &str =
+    r#"{"id":"593533d2-ff31-46e0-b22e-ca754147a96a","amount":"1.00","currency":"ADA"}"#;
+
+const PROFILE: &str = r#"{"id":"6e14a84c-610b-4c63-8b69-443920dffcaf","user_id":"5eeac63c90b913bf3cf7c92e","name":"default","active":true,"is_default":true,"created_at":"2020-06-18T01:37:48.078524Z"}"#;
+
+fn request_body(transport: &FakeTransport) -> serde_json::Value {
+    serde_json::from_str(transport.last_request().body.as_deref().unwrap()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_access_headers() {
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0").build();
+    private_client(&transport).place_order(order).await.unwrap();
+
+    let request = transport.last_request();
+    let header = |name: &str| request.headers[name].to_str().unwrap().to_string();
+    assert_eq!(header("cb-access-key"), "4a9f6de8bcdee641a0a207613dfb43ef");
+    assert_eq!(header("cb-access-passphrase"), "t9riylyad0r");
+    assert_eq!(header("content-type"), "application/json");
+    let mut hmac = crypto::hmac::Hmac::new(
+        crypto::sha2::Sha256::new(),
+        &base64::decode(SECRET).unwrap(),
+    );
+    hmac.input(
+        format!(
+            "{}POST/orders{}",
+            header("cb-access-timestamp"),
+            request.body.as_deref().unwrap()
+        )
+        .as_bytes(),
+    );
+    assert_eq!(
+        header("cb-access-sign"),
+        base64::encode(hmac.result().code())
+    );
 }
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_accounts() {
-    let client = create_client();
-    let _accounts = client.get_accounts().await.unwrap();
+    let transport =
+        FakeTransport::new().respond("GET", "/accounts", 200, &format!("[{}]", ACCOUNT));
+    let accounts = private_client(&transport).get_accounts().await.unwrap();
+    assert_eq!(accounts[0].currency, "BTC");
+    assert!(accounts[0].trading_enabled);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_account() {
-    let client = create_client();
-    let _account = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/accounts/0589d87c-154d-4f5b-9ed9-ff814f70e04a",
+        200,
+        ACCOUNT,
+    );
+    let account = private_client(&transport)
         .get_account("0589d87c-154d-4f5b-9ed9-ff814f70e04a")
         .await
         .unwrap();
+    assert_eq!(account.profile_id, "75da88c5-05bf-4f54-bc85-5c775bd68254");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_place_order_market_funds() {
-    let order = OrderBuilder::market(
-        OrderSide::Buy,
-        "BTC-USD",
-        SizeOrFunds::Funds("10.00".to_owned()),
-    )
-    .build();
-    let client = create_client();
-    let _res = client.place_order(order).await.unwrap();
+    let order =
+        OrderBuilder::market(OrderSide::Buy, "BTC-USD", SizeOrFunds::funds("10.00")).build();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    let id = private_client(&transport).place_order(order).await.unwrap();
+    assert_eq!(id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    let body = request_body(&transport);
+    assert_eq!(body["type"], "market");
+    assert_eq!(body["funds"], "10.00");
+    assert_eq!(body["size"], serde_json::Value::Null);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_place_order_market_size() {
-    let order =
-        OrderBuilder::market(OrderSide::Buy, "ADA", SizeOrFunds::Size("5.00".to_owned())).build();
-    let client = create_client();
-    let _res = client.place_order(order).await.unwrap();
+    let order = OrderBuilder::market(
+        OrderSide::Sell,
+        "ADA-USD",
+        SizeOrFunds::Size("5.00".to_owned()),
+    )
+    .build();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    private_client(&transport).place_order(order).await.unwrap();
+    let body = request_body(&transport);
+    assert_eq!(body["side"], "sell");
+    assert_eq!(body["size"], "5.00");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_place_order_limit() {
-    let order = Order::limit_builder(OrderSide::Buy, "BTC-USD", "36000.0", "1.0").build();
-    let client = create_client();
-    let _res = client.place_order(order).await.unwrap();
+    let order = Order::limit_builder(OrderSide::Buy, "BTC-USD", "36000.0", "1.0")
+        .time_in_force(TimeInForce::GoodTillTime {
+            cancel_after: CancelAfter::Hour,
+            post_only: true,
+        })
+        .build();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    private_client(&transport).place_order(order).await.unwrap();
+    let body = request_body(&transport);
+    assert_eq!(body["type"], "limit");
+    assert_eq!(body["price"], "36000.0");
+    assert_eq!(body["time_in_force"], "GTT");
+    assert_eq!(body["cancel_after"], "hour");
+    assert_eq!(body["post_only"], true);
+}
+
+async fn place_order_body(order: Order) -> serde_json::Value {
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    private_client(&transport).place_order(order).await.unwrap();
+    request_body(&transport)
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[allow(deprecated)]
 async fn test_place_order_stop() {
     let order = OrderBuilder::stop(
         OrderSide::Buy,
@@ -62,28 +169,214 @@ async fn test_place_order_stop() {
         OrderStop::Loss,
     )
     .build();
-    let client = create_client();
-    let _res = client.place_order(order).await.unwrap();
+    let body = place_order_body(order).await;
+    assert_eq!(body["type"], "stop");
+    assert_eq!(body["stop_limit_price"], "36000.0");
+    assert_eq!(body["stop"], "loss");
+    assert_eq!(body["stop_price"], "37000.0");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_stop_limit() {
+    let order = OrderBuilder::stop_limit(
+        OrderSide::Sell,
+        "BTC-USD",
+        OrderStop::Loss,
+        "31000.00",
+        "30900.00",
+        "0.5",
+    )
+    .build();
+    assert_eq!(
+        place_order_body(order).await,
+        serde_json::json!({
+            "type": "stop",
+            "side": "sell",
+            "product_id": "BTC-USD",
+            "stop": "loss",
+            "stop_price": "31000.00",
+            "stop_limit_price": "30900.00",
+            "size": "0.5",
+        })
+    );
+
+    let order = Order::stop_limit_builder(
+        OrderSide::Buy,
+        "BTC-USD",
+        OrderStop::Entry,
+        "40000.00",
+        "40100.00",
+        "0.5",
+    )
+    .client_oid("1f6a7175-a89c-494f-986d-af9987e6dd69".to_string())
+    .build();
+    assert_eq!(
+        place_order_body(order).await,
+        serde_json::json!({
+            "type": "stop",
+            "side": "buy",
+            "product_id": "BTC-USD",
+            "stop": "entry",
+            "stop_price": "40000.00",
+            "stop_limit_price": "40100.00",
+            "size": "0.5",
+            "client_oid": "1f6a7175-a89c-494f-986d-af9987e6dd69",
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_stop_market() {
+    let order = OrderBuilder::stop_market(
+        OrderSide::Buy,
+        "BTC-USD",
+        OrderStop::Entry,
+        "40000.00",
+        SizeOrFunds::Funds("100.00".to_string()),
+    )
+    .build();
+    assert_eq!(
+        place_order_body(order).await,
+        serde_json::json!({
+            "type": "market",
+            "side": "buy",
+            "product_id": "BTC-USD",
+            "stop": "entry",
+            "stop_price": "40000.00",
+            "funds": "100.00",
+        })
+    );
+
+    let order = Order::stop_market_builder(
+        OrderSide::Sell,
+        "BTC-USD",
+        OrderStop::Loss,
+        "31000.00",
+        SizeOrFunds::size("0.5"),
+    )
+    .self_trade_prevention(SelfTradePrevention::CancelOldest)
+    .build();
+    assert_eq!(
+        place_order_body(order).await,
+        serde_json::json!({
+            "type": "market",
+            "side": "sell",
+            "product_id": "BTC-USD",
+            "stop": "loss",
+            "stop_price": "31000.00",
+            "size": "0.5",
+            "self_trade_prevention": "co",
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_rejected() {
+    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "100000.0").build();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        400,
+        r#"{"message":"Insufficient funds"}"#,
+    );
+    let error = private_client(&transport)
+        .place_order(order)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.kind,
+        coinbase_client::error::ErrorKind::InsufficientFunds(_)
+    ));
+    assert_eq!(
+        error.to_string(),
+        "status code: 400, message: Insufficient funds (POST /orders)"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_with_client_oid() {
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    let client = private_client(&transport);
+
+    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0").build();
+    let placed = client.place_order_with_client_oid(order).await.unwrap();
+    assert_eq!(placed.id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    assert_eq!(
+        uuid::Uuid::parse_str(&placed.client_oid)
+            .unwrap()
+            .get_version_num(),
+        4
+    );
+    assert_eq!(
+        request_body(&transport)["client_oid"],
+        placed.client_oid.as_str()
+    );
+
+    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0")
+        .client_oid("8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e".to_string())
+        .build();
+    let placed = client.place_order_with_client_oid(order).await.unwrap();
+    assert_eq!(placed.client_oid, "8c3d0f1e-2b7a-4e59-9d61-0a4f5b6c7d8e");
+    assert_eq!(
+        request_body(&transport)["client_oid"],
+        placed.client_oid.as_str()
+    );
+}
+
+#[test]
+fn test_generate_client_oid() {
+    let build = || {
+        OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0")
+            .generate_client_oid()
+            .build()
+    };
+    let (first, second) = (build(), build());
+    assert!(uuid::Uuid::parse_str(first.client_oid().unwrap()).is_ok());
+    assert_ne!(first.client_oid(), second.client_oid());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_cancel_order() {
-    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "33000.0", "1.0").build();
-    let client = create_client();
-    let order_to_cancel_id = client.place_order(order).await.unwrap();
-    let _canceled_order_id = client.cancel_order(&order_to_cancel_id).await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "DELETE",
+        "/orders/d0c5340b-6d6c-49d9-b567-48c4bfca13d2",
+        200,
+        r#""d0c5340b-6d6c-49d9-b567-48c4bfca13d2""#,
+    );
+    let canceled_order_id = private_client(&transport)
+        .cancel_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .await
+        .unwrap();
+    assert_eq!(canceled_order_id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_cancel_orders() {
-    let client = create_client();
-    let _canceled_orders_ids = client.cancel_orders().await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "DELETE",
+        "/orders",
+        200,
+        r#"["144c6f8e-713f-4682-8435-5280fbe8b2b4","debe4907-95dc-442f-af3b-cec12f42ebda"]"#,
+    );
+    let canceled_orders_ids = private_client(&transport).cancel_orders().await.unwrap();
+    assert_eq!(canceled_orders_ids.len(), 2);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_orders() {
-    let client = create_client();
-    let _orders = client
+    let transport = FakeTransport::new().respond_with_headers(
+        "GET",
+        "/orders?status=open&status=active&status=pending&before=2021-06-19T20:24:20.467086Z",
+        200,
+        &[("cb-before", "2021-06-19T20:24:20.467086Z")],
+        &format!("[{}]", ORDER),
+    );
+    let orders = private_client(&transport)
         .get_orders(
             Some(OrderStatus::OpenActivePending),
             Some("2021-06-19T20:24:20.467086Z"),
@@ -92,45 +385,285 @@ async fn test_get_orders() {
         )
         .await
         .unwrap();
+    assert_eq!(orders.items[0].status, OrderState::Open);
+    assert_eq!(
+        orders.before.as_deref(),
+        Some("2021-06-19T20:24:20.467086Z")
+    );
+    assert!(orders.after.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_orders() {
+    let transport = FakeTransport::new()
+        .respond_with_headers(
+            "GET",
+            "/orders?status=open&",
+            200,
+            &[("cb-after", "2021-06-19T20:24:20.467086Z")],
+            &format!("[{}]", ORDER),
+        )
+        .respond(
+            "GET",
+            "/orders?status=open&after=2021-06-19T20:24:20.467086Z",
+            200,
+            "[]",
+        );
+    let orders: Vec<OrderInfo> = private_client(&transport)
+        .stream_orders(Some(OrderStatus::Open), None, None)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(transport.requests().len(), 2);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_order() {
-    let order = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0").build();
-    let client = create_client();
-    let order_id = client.place_order(order).await.unwrap();
-    let _order = client.get_order(&order_id).await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/orders/d0c5340b-6d6c-49d9-b567-48c4bfca13d2",
+        200,
+        ORDER,
+    );
+    let order = private_client(&transport)
+        .get_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .await
+        .unwrap();
+    assert_eq!(order.product_id, "BTC-USD");
+    assert_eq!(order.side, OrderSide::Buy);
+    assert_eq!(order.r#type, OrderType::Limit);
+    assert_eq!(order.time_in_force, Some(OrderTimeInForce::GoodTillCancel));
+    assert!(order.done_at.is_none());
+    assert_eq!(
+        order.created_at,
+        Utc.ymd(2021, 6, 19).and_hms_micro(20, 24, 20, 467086)
+    );
+}
+
+async fn get_order_fixture(order: &str) -> OrderInfo {
+    let id = serde_json::from_str::<serde_json::Value>(order).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let transport = FakeTransport::new().respond("GET", &format!("/orders/{}", id), 200, order);
+    private_client(&transport).get_order(&id).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_get_market_order() {
+    let order = get_order_fixture(MARKET_ORDER).await;
+    assert_eq!(order.r#type, OrderType::Market);
+    assert!(order.price.is_none());
+    assert!(order.size.is_none());
+    assert!(order.time_in_force.is_none());
+    assert_eq!(order.funds.unwrap().to_string(), "99.50248756");
+    assert_eq!(order.specified_funds.unwrap().to_string(), "100.00000000");
+    assert_eq!(order.status, OrderState::Done);
+    assert_eq!(order.done_reason, Some(DoneReason::Filled));
+    assert_eq!(
+        order.done_at,
+        Some(Utc.ymd(2021, 6, 19).and_hms_milli(20, 24, 20, 490))
+    );
+    assert_eq!(
+        order.profile_id.as_deref(),
+        Some("75da88c5-05bf-4f54-bc85-5c775bd68254")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_get_stop_order() {
+    let order = get_order_fixture(STOP_ORDER).await;
+    assert_eq!(order.status, OrderState::Active);
+    assert_eq!(order.side, OrderSide::Sell);
+    assert_eq!(order.stop, Some(OrderStop::Loss));
+    assert_eq!(order.stop_price.unwrap().to_string(), "31000.00000000");
+    assert_eq!(order.price.unwrap().to_string(), "30000.00000000");
+    assert!(order.done_reason.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_get_good_till_time_order() {
+    let order = get_order_fixture(GTT_ORDER).await;
+    assert_eq!(order.time_in_force, Some(OrderTimeInForce::GoodTillTime));
+    assert_eq!(
+        order.expire_time,
+        Some(Utc.ymd(2021, 6, 19).and_hms_micro(21, 24, 20, 467086))
+    );
+    assert!(order.post_only);
+    assert_eq!(order.done_reason, Some(DoneReason::Canceled));
+    assert!(order.stop.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_get_order_unknown_values() {
+    let order = STOP_ORDER
+        .replace(r#""stop":"loss""#, r#""stop":"trailing""#)
+        .replace(
+            r#""status":"active""#,
+            r#""status":"done","done_reason":"expired""#,
+        );
+    let order = get_order_fixture(&order).await;
+    assert_eq!(
+        order.done_reason,
+        Some(DoneReason::Unknown("expired".to_string()))
+    );
+    assert_eq!(order.stop, Some(OrderStop::Unknown("trailing".to_string())));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_fill_by_order_id() {
-    let client = create_client();
-    let _fills = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/fills?order_id=4f2756cf-dcb5-492b-83e5-5f2141892758&",
+        200,
+        &format!("[{}]", FILL),
+    );
+    let fills = private_client(&transport)
         .get_fill_by_order_id("4f2756cf-dcb5-492b-83e5-5f2141892758", None, None, None)
         .await
         .unwrap();
+    assert_eq!(fills.items[0].trade_id, 74);
+    assert_eq!(fills.items[0].liquidity, Liquidity::Taker);
+    assert_eq!(fills.items[0].side, OrderSide::Buy);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_unknown_enum_values() {
+    let order = ORDER
+        .replace(r#""status":"open""#, r#""status":"expired""#)
+        .replace(r#""type":"limit""#, r#""type":"iceberg""#);
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/orders/d0c5340b-6d6c-49d9-b567-48c4bfca13d2",
+        200,
+        &order,
+    );
+    let order = private_client(&transport)
+        .get_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2")
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderState::Unknown("expired".to_string()));
+    assert_eq!(order.r#type.as_str(), "iceberg");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_fills_by_product_id() {
-    let product_id = "BTC-USD";
-    let client = create_client();
-    let _fills = client
-        .get_fills_by_product_id(&product_id, None, None, None)
+    let transport = FakeTransport::new().respond_with_headers(
+        "GET",
+        "/fills?product_id=BTC-USD&after=29786034",
+        200,
+        &[("cb-before", "74"), ("cb-after", "74")],
+        &format!("[{}]", FILL),
+    );
+    let fills = private_client(&transport)
+        .get_fills_by_product_id("BTC-USD", None, Some("29786034"), None)
+        .await
+        .unwrap();
+    assert_eq!(fills.items[0].fee.to_string(), "0.00025");
+    assert_eq!(fills.after.as_deref(), Some("74"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_fills_by_product_id() {
+    let transport = FakeTransport::new()
+        .respond_with_headers(
+            "GET",
+            "/fills?product_id=BTC-USD&limit=1",
+            200,
+            &[("cb-before", "74"), ("cb-after", "74")],
+            &format!("[{}]", FILL),
+        )
+        // the last page has no `after` cursor
+        .respond(
+            "GET",
+            "/fills?product_id=BTC-USD&after=74&limit=1",
+            200,
+            &format!("[{}]", FILL.replace("\"trade_id\":74", "\"trade_id\":73")),
+        );
+    let fills: Vec<Fill> = private_client(&transport)
+        .stream_fills_by_product_id("BTC-USD", Some(1), None)
+        .try_collect()
         .await
         .unwrap();
+    assert_eq!(
+        fills.iter().map(|fill| fill.trade_id).collect::<Vec<_>>(),
+        vec![74, 73]
+    );
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_error() {
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/fills?product_id=BTC-USD&",
+        401,
+        r#"{"message":"invalid signature"}"#,
+    );
+    let fills: Result<Vec<Fill>, _> = private_client(&transport)
+        .stream_fills_by_product_id("BTC-USD", None, None)
+        .try_collect()
+        .await;
+    assert!(fills.is_err());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_limits() {
-    let client = create_client();
-    let _limits = client.get_limits().await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/users/self/exchange-limits",
+        200,
+        r#"{"limit_currency":"USD","transfer_limits":{"ach":{"BTC":{"max":21276.59,"remaining":21276.59,"period_in_days":7},"USD":{"max":25000,"remaining":20000,"period_in_days":7}},"buy":{"BTC":{"max":10000,"remaining":10000,"period_in_days":7}},"exchange_withdraw":{"BTC":{"max":1000000,"remaining":1000000}}}}"#,
+    );
+    let limits = private_client(&transport).get_limits().await.unwrap();
+    assert_eq!(limits.limit_currency, "USD");
+    let ach = &limits.transfer_limits["ach"];
+    assert_eq!(ach["USD"].remaining.to_string(), "20000");
+    assert_eq!(ach["BTC"].period_in_days, Some(7));
+    assert!(limits.transfer_limits["exchange_withdraw"]["BTC"]
+        .period_in_days
+        .is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_oracle() {
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/oracle",
+        200,
+        r#"{"timestamp":"1583195060","messages":["0x00","0x01"],"signatures":["0x02","0x03"],"prices":{"BTC":"8846.945","ETH":"226.535"}}"#,
+    );
+    let oracle = private_client(&transport).oracle().await.unwrap();
+    assert_eq!(oracle.timestamp, Utc.timestamp(1583195060, 0));
+    assert_eq!(oracle.messages.len(), 2);
+    assert_eq!(oracle.prices["BTC"].to_string(), "8846.945");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_oracle_timestamp_out_of_range() {
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/oracle",
+        200,
+        r#"{"timestamp":"9999999999999999","messages":[],"signatures":[],"prices":{}}"#,
+    );
+    let err = private_client(&transport).oracle().await.unwrap_err();
+    assert!(matches!(
+        err.kind,
+        coinbase_client::error::ErrorKind::JSON(_)
+    ));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_deposits() {
-    let client = create_client();
-    let _deposits = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers?type=deposit&profile_id=b7482eaa-3eea-4065-9d81-1484257c5f92&",
+        200,
+        TRANSFERS,
+    );
+    let deposits = private_client(&transport)
         .get_deposits(
             Some("b7482eaa-3eea-4065-9d81-1484257c5f92"),
             None,
@@ -139,73 +672,177 @@ async fn test_get_deposits() {
         )
         .await
         .unwrap();
+    let deposit = &deposits.items[0];
+    assert_eq!(deposit.amount.to_string(), "10.00000000");
+    assert_eq!(deposit.r#type, TransferType::Deposit);
+    assert_eq!(
+        deposit.created_at,
+        Utc.ymd(2021, 6, 18).and_hms_micro(1, 37, 48, 78524)
+    );
+    assert_eq!(deposit.status(), TransferStatus::Completed);
+    assert!(deposit.user_nonce.is_none());
+    assert_eq!(
+        deposit.details.coinbase_account_id.as_deref(),
+        Some("2b760113-fbba-5600-ac74-36482c130768")
+    );
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_internal_deposits() {
-    let client = create_client();
-    let _deposits = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers?type=internal_deposit&profile_id=e1d7731f-b7e2-4285-b711-eeec76fc2aff&limit=10",
+        200,
+        "[]",
+    );
+    let deposits = private_client(&transport)
         .get_internal_deposits(
             Some("e1d7731f-b7e2-4285-b711-eeec76fc2aff"),
             None,
             None,
-            None,
+            Some(10),
         )
         .await
         .unwrap();
+    assert!(deposits.items.is_empty());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_deposit() {
-    let client = create_client();
-    let _deposit = client
-        .get_deposit("80259339-7bf9-498f-8210-ddbd32a1c545")
-        .await;
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers/80259339-7bf9-498f-8200-ddbd32a1c545",
+        200,
+        &TRANSFERS[1..TRANSFERS.len() - 1],
+    );
+    let deposit = private_client(&transport)
+        .get_deposit("80259339-7bf9-498f-8200-ddbd32a1c545")
+        .await
+        .unwrap();
+    assert_eq!(deposit.id, "80259339-7bf9-498f-8200-ddbd32a1c545");
+    assert_eq!(deposit.r#type, TransferType::Deposit);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_payment_methods() {
-    let client = create_client();
-    let _payment_methods = client.get_payment_methods().await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/payment-methods",
+        200,
+        r#"[{"id":"bc6d7162-d984-5ffa-963c-a493b1c1370b","type":"ach_bank_account","name":"Bank of America - eBan... ********7134","currency":"USD","primary_buy":true,"primary_sell":true,"allow_buy":true,"allow_sell":true,"allow_deposit":true,"allow_withdraw":true,"limits":{"buy":[{"period_in_days":1,"total":{"amount":"10000.00","currency":"USD"},"remaining":{"amount":"10000.00","currency":"USD"}}],"instant_buy":[{"period_in_days":7,"total":{"amount":"0.00","currency":"USD"},"remaining":{"amount":"0.00","currency":"USD"}}],"sell":[{"period_in_days":1,"total":{"amount":"10000.00","currency":"USD"},"remaining":{"amount":"10000.00","currency":"USD"}}],"deposit":[{"period_in_days":1,"total":{"amount":"10000.00","currency":"USD"},"remaining":{"amount":"7500.00","currency":"USD"}}]}},{"id":"e49c8d15-547b-464e-ac3d-4b9d20b360ec","type":"fiat_account","name":"USD Wallet","currency":"USD","primary_buy":false,"primary_sell":false,"allow_buy":true,"allow_sell":true,"allow_deposit":true,"allow_withdraw":true}]"#,
+    );
+    let payment_methods = private_client(&transport)
+        .get_payment_methods()
+        .await
+        .unwrap();
+    assert_eq!(payment_methods[0].r#type, PaymentMethodType::AchBankAccount);
+    let deposit = &payment_methods[0].limits.deposit[0];
+    assert_eq!(deposit.period_in_days, 1);
+    assert_eq!(deposit.remaining.amount.to_string(), "7500.00");
+    assert_eq!(deposit.total.currency, "USD");
+    assert_eq!(payment_methods[1].r#type, PaymentMethodType::FiatAccount);
+    assert!(payment_methods[1].limits.buy.is_empty());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_coinbase_accounts() {
-    let client = create_client();
-    let _accounts = client.get_coinbase_accounts().await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/coinbase-accounts",
+        200,
+        r#"[{"id":"fc3a8a57-7142-542d-8436-95a3d82e1622","name":"ETH Wallet","balance":"0.00000000","currency":"ETH","type":"wallet","primary":false,"active":true},{"id":"2ae3354e-f1c3-5771-8a37-6228e9d239db","name":"USD Wallet","balance":"0.00","currency":"USD","type":"fiat","primary":false,"active":true,"wire_deposit_information":{"account_number":"0199003122","routing_number":"026013356","bank_name":"Metropolitan Bank","bank_address":"99 Park Ave 4th Fl New York, NY 10016","bank_country":{"code":"US","name":"United States"},"account_name":"Coinbase, Inc","account_address":"548 Market Street, #23008, San Francisco, CA 94104","reference":"BAOCAEUX"}},{"id":"1bfad868-5223-5d3c-8a22-b5ed371e55cb","name":"EUR Wallet","balance":"0.00","currency":"EUR","type":"fiat","primary":false,"active":true,"sepa_deposit_information":{"iban":"EE957700771001355096","swift":"LHVBEE22","bank_name":"AS LHV Pank","bank_address":"Tartu mnt 2, 10145 Tallinn, Estonia","bank_country_name":"Estonia","account_name":"Coinbase UK, Ltd.","account_address":"9th Floor, 107 Cheapside, London, EC2V 6DN, United Kingdom","reference":"CBAEUXOVFXOXYX"}}]"#,
+    );
+    let accounts = private_client(&transport)
+        .get_coinbase_accounts()
+        .await
+        .unwrap();
+    assert_eq!(accounts[0].currency, "ETH");
+    assert_eq!(accounts[0].r#type, CoinbaseAccountType::Wallet);
+    assert!(accounts[0].wire_deposit_information.is_none());
+    let wire = accounts[1].wire_deposit_information.as_ref().unwrap();
+    assert_eq!(wire.routing_number, "026013356");
+    assert_eq!(wire.bank_country.code, "US");
+    let sepa = accounts
+        .iter()
+        .find(|account| account.r#type == CoinbaseAccountType::Fiat && account.currency == "EUR")
+        .and_then(|account| account.sepa_deposit_information.as_ref())
+        .unwrap();
+    assert_eq!(sepa.iban, "EE957700771001355096");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_deposit_funds() {
-    let client = create_client();
-    let _deposit = client
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/deposits/payment-method",
+        200,
+        r#"{"id":"593533d2-ff31-46e0-b22e-ca754147a96a","amount":"10.00","currency":"USD","payout_at":"2016-08-20T00:31:09Z"}"#,
+    );
+    let deposit = private_client(&transport)
         .deposit_funds("10.00", "USD", "9da3e279-20a1-57e4-95f8-52ec41041999")
         .await
         .unwrap();
+    assert_eq!(deposit.payout_at.as_deref(), Some("2016-08-20T00:31:09Z"));
+    assert_eq!(
+        request_body(&transport),
+        serde_json::json!({
+            "amount": "10.00",
+            "currency": "USD",
+            "payment_method_id": "9da3e279-20a1-57e4-95f8-52ec41041999"
+        })
+    );
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_deposit_from_coinbase() {
-    let client = create_client();
-    let _deposit = client
-        .deposit_funds_from_coinbase(13.468564, "ALGO", "2141660b-da3d-5060-8af1-b8478cf6dd44")
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/deposits/coinbase-account",
+        200,
+        r#"{"id":"593533d2-ff31-46e0-b22e-ca754147a96a","amount":"13.468564","currency":"ALGO"}"#,
+    );
+    let deposit = private_client(&transport)
+        .deposit_funds_from_coinbase("13.468564", "ALGO", "2141660b-da3d-5060-8af1-b8478cf6dd44")
         .await
         .unwrap();
+    assert_eq!(deposit.currency, "ALGO");
+    assert!(deposit.payout_at.is_none());
+    // amounts are sent as strings so that no precision is lost
+    assert_eq!(request_body(&transport)["amount"], "13.468564");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_generate_crypto_address() {
-    let client = create_client();
-    let _address = client
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/coinbase-accounts/2141660b-da3d-5060-8af1-b8478cf6dd44/addresses",
+        200,
+        r#"{"id":"fc3a8a57-7142-542d-8436-95a3d82e1622","address":"0x82289D45Ee8E806C63Ba0DC94a22d4238525d815","address_info":{"address":"0x82289D45Ee8E806C63Ba0DC94a22d4238525d815"},"name":"New exchange deposit address","created_at":"2021-06-19T20:24:20Z","updated_at":"2021-06-19T20:24:20Z","network":"ethereum","uri_scheme":"ethereum","resource":"address","resource_path":"/v2/accounts/2141660b-da3d-5060-8af1-b8478cf6dd44/addresses/fc3a8a57-7142-542d-8436-95a3d82e1622","warnings":[{"title":"Only send ETH to this address","details":"Sending any other digital asset will result in permanent loss.","image_url":"https://www.coinbase.com/assets/addresses/global-receive-warning.png"}],"deposit_uri":"ethereum:0x82289D45Ee8E806C63Ba0DC94a22d4238525d815","callback_url":null,"exchange_deposit_address":true}"#,
+    );
+    let address = private_client(&transport)
         .generate_crypto_deposit_address("2141660b-da3d-5060-8af1-b8478cf6dd44")
         .await
         .unwrap();
+    assert_eq!(
+        address.address,
+        "0x82289D45Ee8E806C63Ba0DC94a22d4238525d815"
+    );
+    assert_eq!(address.network.as_deref(), Some("ethereum"));
+    assert!(address.destination_tag.is_none());
+    assert_eq!(address.warnings[0].title, "Only send ETH to this address");
+    assert_eq!(address.exchange_deposit_address, Some(true));
+    assert!(transport.last_request().body.is_none());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_withdrawals() {
-    let client = create_client();
-    let _withdrawals = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers?type=withdraw&profile_id=b7482eaa-3eea-4065-9d81-1484257c5f92&",
+        200,
+        &TRANSFERS.replace("\"deposit\"", "\"withdraw\""),
+    );
+    let withdrawals = private_client(&transport)
         .get_withdrawals(
             Some("b7482eaa-3eea-4065-9d81-1484257c5f92"),
             None,
@@ -214,44 +851,93 @@ async fn test_get_withdrawals() {
         )
         .await
         .unwrap();
+    assert_eq!(withdrawals.items[0].r#type, TransferType::Withdraw);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_internal_withdrawals() {
-    let client = create_client();
-    let _withdrawals = client
-        .get_internal_withdrawals(
-            Some("b7482eaa-3eea-4065-9d81-1484257c5f92"),
-            None,
-            None,
-            None,
-        )
+    let transport =
+        FakeTransport::new().respond("GET", "/transfers?type=internal_withdraw&", 200, "[]");
+    let withdrawals = private_client(&transport)
+        .get_internal_withdrawals(None, None, None, None)
         .await
         .unwrap();
+    assert!(withdrawals.items.is_empty());
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_withdrawal() {
-    let client = create_client();
-    let _withdrawal = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers/0e94a87f-9d50-4ead-86ac-7898830c5edf",
+        200,
+        &TRANSFERS[1..TRANSFERS.len() - 1].replace("\"deposit\"", "\"withdraw\""),
+    );
+    let withdrawal = private_client(&transport)
+        .get_withdrawal("0e94a87f-9d50-4ead-86ac-7898830c5edf")
+        .await
+        .unwrap();
+    assert_eq!(withdrawal.r#type, TransferType::Withdraw);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_crypto_withdrawal() {
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/transfers/0e94a87f-9d50-4ead-86ac-7898830c5edf",
+        200,
+        r#"{"id":"0e94a87f-9d50-4ead-86ac-7898830c5edf","type":"withdraw","created_at":"2021-06-18 01:37:48.078524+00","completed_at":null,"canceled_at":null,"processed_at":"2021-06-18 01:40:02.12+00","account_id":"bf091906-ca7f-499e-95fa-5bc15e918b46","user_id":"5eeac63c90b913bf3cf7c92e","user_nonce":"1624386512345","amount":"25.00000000","details":{"destination_tag":"1234567","sent_to_address":"rw2ciyaNshpHe7bCHo4bRWq6pqqynnWKQg","crypto_address":"rw2ciyaNshpHe7bCHo4bRWq6pqqynnWKQg","crypto_transaction_hash":"7F1B9A2C4D5E6F708192A3B4C5D6E7F8091A2B3C4D5E6F708192A3B4C5D6E7F8","coinbase_account_id":"2b760113-fbba-5600-ac74-36482c130768","coinbase_withdrawal_id":"60d0d5b7c1f1b60f8c4d5e6a"}}"#,
+    );
+    let withdrawal = private_client(&transport)
         .get_withdrawal("0e94a87f-9d50-4ead-86ac-7898830c5edf")
         .await
         .unwrap();
+    assert_eq!(withdrawal.status(), TransferStatus::Processed);
+    assert_eq!(withdrawal.user_nonce.as_deref(), Some("1624386512345"));
+    assert_eq!(
+        withdrawal.details.crypto_address.as_deref(),
+        Some("rw2ciyaNshpHe7bCHo4bRWq6pqqynnWKQg")
+    );
+    assert_eq!(
+        withdrawal.details.destination_tag.as_deref(),
+        Some("1234567")
+    );
+    assert!(withdrawal
+        .details
+        .crypto_transaction_hash
+        .unwrap()
+        .starts_with("7F1B9A2C"));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_account_history() {
-    let client = create_client();
-    let _history = client
+    let transport = FakeTransport::new().respond_with_headers(
+        "GET",
+        "/accounts/680f85f4-1a99-4108-93ce-a9066f9de246/ledger?limit=2",
+        200,
+        &[("cb-before", "297946691"), ("cb-after", "296147671")],
+        r#"[{"id":"297946691","amount":"-0.0100000000000000","balance":"0.0000000000000000","created_at":"2021-06-19T20:24:20.467086Z","type":"match","details":{"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","trade_id":"74","product_id":"BTC-USD"}},{"id":"296147671","amount":"100.0000000000000000","balance":"100.0000000000000000","created_at":"2021-06-18T01:37:48.078524Z","type":"transfer","details":{}}]"#,
+    );
+    let history = private_client(&transport)
         .get_account_history("680f85f4-1a99-4108-93ce-a9066f9de246", None, None, Some(2))
         .await
         .unwrap();
+    assert_eq!(history.items[0].r#type, LedgerEntryType::Match);
+    assert_eq!(history.items[1].r#type, LedgerEntryType::Transfer);
+    assert_eq!(history.items[0].details.trade_id.as_deref(), Some("74"));
+    assert!(history.items[1].details.order_id.is_none());
+    assert_eq!(history.after.as_deref(), Some("296147671"));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_account_holds() {
-    let client = create_client();
-    let _holds = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/accounts/680f85f4-1a99-4108-93ce-a9066f9de246/holds?limit=100",
+        200,
+        r#"[{"id":"82dcd140-c3c7-4507-8de4-2c529cd1a28f","account_id":"680f85f4-1a99-4108-93ce-a9066f9de246","created_at":"2021-06-19T20:24:20.467086Z","updated_at":"2021-06-19T20:24:20.467086Z","amount":"4.23","type":"order","ref":"0a205de4-dd35-4370-a285-fe8fc375a273"}]"#,
+    );
+    let holds = private_client(&transport)
         .get_account_holds(
             "680f85f4-1a99-4108-93ce-a9066f9de246",
             None,
@@ -260,35 +946,100 @@ async fn test_get_account_holds() {
         )
         .await
         .unwrap();
+    assert_eq!(holds.items[0].r#ref, "0a205de4-dd35-4370-a285-fe8fc375a273");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_fees() {
-    let client = create_client();
-    let _fees = client.get_fees().await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/fees",
+        200,
+        r#"{"maker_fee_rate":"0.0015","taker_fee_rate":"0.0025","usd_volume":"25000.00"}"#,
+    );
+    let fees = private_client(&transport).get_fees().await.unwrap();
+    assert_eq!(fees.taker_fee_rate.to_string(), "0.0025");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_fee_estimate_() {
-    let client = create_client();
-    let _fee = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/withdrawals/fee-estimate?currency=ETH&crypto_address=0x82289D45Ee8E806C63Ba0DC94a22d4238525d815",
+        200,
+        r#"{"fee":0.01}"#,
+    );
+    let fee = private_client(&transport)
         .get_fee_estimate("ETH", "0x82289D45Ee8E806C63Ba0DC94a22d4238525d815")
         .await
         .unwrap();
+    assert_eq!(fee.to_string(), "0.01");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_stablecoin_conversion() {
-    let client = create_client();
-    let _conversion = client
-        .convert_stablecoin("USD", "USDC", 10.00)
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/conversions",
+        200,
+        r#"{"id":"8942caee-f9d5-4600-a894-4811268545db","amount":"10.00000000","from_account_id":"7849cc79-8b01-4793-9345-bc6b5f08acce","to_account_id":"105c3e58-0898-4106-8283-dc5781cda07b","from":"USD","to":"USDC"}"#,
+    );
+    let conversion = private_client(&transport)
+        .convert_stablecoin("USD", "USDC", "10.00")
+        .await
+        .unwrap();
+    assert_eq!(conversion.to, "USDC");
+}
+
+#[cfg(feature = "decimal")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_decimal_amounts() {
+    use coinbase_client::ToDecimal;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/conversions",
+        200,
+        r#"{"id":"8942caee-f9d5-4600-a894-4811268545db","amount":"10.00000001","from_account_id":"7849cc79-8b01-4793-9345-bc6b5f08acce","to_account_id":"105c3e58-0898-4106-8283-dc5781cda07b","from":"USD","to":"USDC"}"#,
+    );
+    let amount = Decimal::from_str("10.00000001").unwrap();
+    let conversion = private_client(&transport)
+        .convert_stablecoin("USD", "USDC", amount)
         .await
         .unwrap();
+    assert_eq!(conversion.amount, "10.00000001");
+    assert_eq!(conversion.amount.to_decimal().unwrap(), amount);
+    assert_eq!(request_body(&transport)["amount"], "10.00000001");
+}
+
+#[cfg(feature = "decimal")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_decimal_size_or_funds() {
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        200,
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+    );
+    let funds = Decimal::from_str("10.01").unwrap();
+    let order = OrderBuilder::market(OrderSide::Buy, "BTC-USD", SizeOrFunds::funds(funds)).build();
+    private_client(&transport).place_order(order).await.unwrap();
+    assert_eq!(request_body(&transport)["funds"], "10.01");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_report() {
-    let client = create_client();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/reports",
+        200,
+        r#"{"id":"d4a3e847-b618-454d-bcb3-e77b0ad61600","type":"account","status":"pending"}"#,
+    );
     let report = Report::account_builder(
         "2014-11-01T00:00:00.000Z",
         "2021-06-11T02:48:15.853Z",
@@ -297,42 +1048,65 @@ async fn test_report() {
     .email("")
     .format(Format::CSV)
     .build();
-    let _response = client.create_report(report).await.unwrap();
+    let response = private_client(&transport)
+        .create_report(report)
+        .await
+        .unwrap();
+    assert_eq!(response.status, "pending");
+    assert!(response.created_at.is_none());
+    assert_eq!(request_body(&transport)["type"], "account");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_report() {
-    let client = create_client();
-    let _report = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/reports/d4a3e847-b618-454d-bcb3-e77b0ad61600",
+        200,
+        r#"{"id":"d4a3e847-b618-454d-bcb3-e77b0ad61600","type":"account","status":"ready","created_at":"2021-06-11T02:48:15.853Z","completed_at":"2021-06-11T02:48:17.853Z","expires_at":"2021-06-18T02:48:15.853Z","file_url":"https://example.com/0428b97b.csv","params":{"start_date":"2014-11-01T00:00:00.000Z","end_date":"2021-06-11T02:48:15.853Z"}}"#,
+    );
+    let report = private_client(&transport)
         .get_report("d4a3e847-b618-454d-bcb3-e77b0ad61600")
         .await
         .unwrap();
+    assert_eq!(
+        report.params.unwrap().start_date,
+        Utc.ymd(2014, 11, 1).and_hms(0, 0, 0)
+    );
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_profiles() {
-    let client = create_client();
-    let _profiles = client.get_profiles().await.unwrap();
+    let transport =
+        FakeTransport::new().respond("GET", "/profiles", 200, &format!("[{}]", PROFILE));
+    let profiles = private_client(&transport).get_profiles().await.unwrap();
+    assert!(profiles[0].is_default);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_profile() {
-    let client = create_client();
-    let _profile = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/profiles/6e14a84c-610b-4c63-8b69-443920dffcaf",
+        200,
+        PROFILE,
+    );
+    let profile = private_client(&transport)
         .get_profile("6e14a84c-610b-4c63-8b69-443920dffcaf")
         .await
         .unwrap();
+    assert_eq!(profile.name, "default");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_create_profile_transfer() {
-    let client = create_client();
-    let ok = client
+    let transport = FakeTransport::new().respond("POST", "/profiles/transfer", 200, "OK");
+    let ok = private_client(&transport)
         .create_profile_transfer(
             "e1d7731f-b7e2-4285-b711-eeec76fc2aff",
             "3510ac37-1a99-4c9c-9865-15f1bc5a832e",
             "USD",
-            10.00,
+            "10.00",
         )
         .await
         .unwrap();
@@ -341,15 +1115,275 @@ async fn test_create_profile_transfer() {
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_withdraw_to_crypto_address() {
-    let client = create_client();
-    let _res = client.withdraw_to_crypto_address(6.0, "ADA", "addr1qyk0yr3ht9d6hcqwp8q8j38nxs04npyjauzz9wp5jcfr95h64lvegfk57zmzltj3nmpjff6490ayyvjh0g6sne6hm3hspnnscy", None, None, None).await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/withdrawals/crypto",
+        200,
+        r#"{"id":"593533d2-ff31-46e0-b22e-ca754147a96a","amount":"6.0","currency":"ADA"}"#,
+    );
+    let res = private_client(&transport).withdraw_to_crypto_address("6.0", "ADA", "addr1qyk0yr3ht9d6hcqwp8q8j38nxs04npyjauzz9wp5jcfr95h64lvegfk57zmzltj3nmpjff6490ayyvjh0g6sne6hm3hspnnscy", None, None, None).await.unwrap();
+    assert_eq!(res["currency"], "ADA");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_withdraw_to_coinbase() {
-    let client = create_client();
-    let _res = client
-        .withdraw_to_coinbase(1.0, "ADA", "91bdfea7-f243-5baa-bb0d-5b93c9f09ffc")
+    let transport =
+        FakeTransport::new().respond("POST", "/withdrawals/coinbase-account", 200, WITHDRAWAL);
+    let res = private_client(&transport)
+        .withdraw_to_coinbase("1.0", "ADA", "91bdfea7-f243-5baa-bb0d-5b93c9f09ffc")
+        .await
+        .unwrap();
+    assert_eq!(res.amount.to_string(), "1.00");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_try_new_invalid_credentials() {
+    let invalid_secret = PrivateClient::try_new(
+        "not base64!".to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+    );
+    let invalid_passphrase = PrivateClient::try_new(
+        SECRET.to_string(),
+        "t9riylyad0r\n".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+    );
+    for result in [invalid_secret, invalid_passphrase] {
+        match result {
+            Err(err) => {
+                assert!(matches!(
+                    err.kind,
+                    coinbase_client::error::ErrorKind::Auth(_)
+                ));
+                assert!(err.is_auth());
+            }
+            Ok(_) => panic!("expected an auth error"),
+        }
+    }
+    PrivateClient::try_new(
+        SECRET.to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+    )
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_signing_error() {
+    let transport = FakeTransport::new().respond("GET", "/accounts", 200, "[]");
+    let client = PrivateClient::with_transport(
+        "not base64!".to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+        transport.clone(),
+    );
+    let err = client.get_accounts().await.unwrap_err();
+    assert!(matches!(
+        err.kind,
+        coinbase_client::error::ErrorKind::Auth(_)
+    ));
+    assert_eq!(err.context.unwrap().path, "/accounts");
+    assert!(transport.requests().is_empty());
+    assert!(client.websocket_auth().is_err());
+}
+
+const ORDER_PATH: &str = "/orders/d0c5340b-6d6c-49d9-b567-48c4bfca13d2";
+
+fn done_order(reason: &str, filled_size: &str) -> String {
+    ORDER
+        .replace(
+            r#""status":"open""#,
+            &format!(r#""status":"done","done_reason":"{}""#, reason),
+        )
+        .replace(
+            r#""filled_size":"0.00000000""#,
+            &format!(r#""filled_size":"{}""#, filled_size),
+        )
+}
+
+fn replace_transport(cancel_status: u16, canceled: Option<&str>) -> FakeTransport {
+    let transport = FakeTransport::new()
+        .respond("GET", ORDER_PATH, 200, ORDER)
+        .respond(
+            "DELETE",
+            ORDER_PATH,
+            cancel_status,
+            r#""d0c5340b-6d6c-49d9-b567-48c4bfca13d2""#,
+        )
+        .respond(
+            "POST",
+            "/orders",
+            200,
+            r#"{"id":"5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39"}"#,
+        );
+    match canceled {
+        Some(canceled) => transport.respond("GET", ORDER_PATH, 200, canceled),
+        None => transport.respond("GET", ORDER_PATH, 404, r#"{"message":"NotFound"}"#),
+    }
+}
+
+async fn replace_order(transport: &FakeTransport) -> ReplaceOutcome {
+    private_client(transport)
+        .replace_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2", "36010.00", "1.0")
+        .await
+        .unwrap()
+}
+
+fn placed_order(transport: &FakeTransport) -> bool {
+    transport
+        .requests()
+        .iter()
+        .any(|request| request.method == Method::POST)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_replace_order() {
+    // an order canceled without fills is not kept
+    let transport = replace_transport(200, None);
+    match replace_order(&transport).await {
+        ReplaceOutcome::Replaced {
+            canceled: None,
+            replacement,
+            size,
+        } => {
+            assert_eq!(replacement.id, "5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39");
+            assert_eq!(size.as_str(), "1.0");
+            let body = request_body(&transport);
+            assert_eq!(body["type"], "limit");
+            assert_eq!(body["side"], "buy");
+            assert_eq!(body["product_id"], "BTC-USD");
+            assert_eq!(body["price"], "36010.00");
+            assert_eq!(body["size"], "1.0");
+            assert_eq!(body["time_in_force"], "GTC");
+            assert_eq!(body["post_only"], false);
+            assert_eq!(body["self_trade_prevention"], "dc");
+            assert_eq!(body["client_oid"], replacement.client_oid.as_str());
+        }
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_replace_order_partially_filled() {
+    let transport = replace_transport(200, Some(&done_order("canceled", "0.25000000")));
+    match replace_order(&transport).await {
+        ReplaceOutcome::Replaced {
+            canceled: Some(canceled),
+            size,
+            ..
+        } => {
+            assert_eq!(canceled.filled_size.to_string(), "0.25000000");
+            // parsed sizes drop their trailing zeros
+            assert_eq!(size.as_str(), "0.75");
+            assert_eq!(request_body(&transport)["size"], "0.75");
+        }
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+
+    let transport = replace_transport(200, Some(&done_order("canceled", "1.00000000")));
+    assert!(matches!(
+        replace_order(&transport).await,
+        ReplaceOutcome::Canceled(_)
+    ));
+    assert!(!placed_order(&transport));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_replace_order_filled() {
+    let transport = FakeTransport::new()
+        .respond("GET", ORDER_PATH, 200, ORDER)
+        .respond(
+            "DELETE",
+            ORDER_PATH,
+            400,
+            r#"{"message":"Order already done"}"#,
+        )
+        .respond("GET", ORDER_PATH, 200, &done_order("filled", "1.00000000"));
+    assert!(matches!(
+        replace_order(&transport).await,
+        ReplaceOutcome::Filled(_)
+    ));
+    assert!(!placed_order(&transport));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_replace_order_not_replaced() {
+    // the cancel is not confirmed yet
+    let transport = replace_transport(200, Some(ORDER));
+    assert!(matches!(
+        replace_order(&transport).await,
+        ReplaceOutcome::CancelPending(_)
+    ));
+    assert!(!placed_order(&transport));
+
+    // the order was canceled before
+    let transport =
+        FakeTransport::new().respond("GET", ORDER_PATH, 200, &done_order("canceled", "0.5"));
+    assert!(matches!(
+        replace_order(&transport).await,
+        ReplaceOutcome::NotOpen(_)
+    ));
+    assert_eq!(transport.requests().len(), 1);
+
+    let transport = FakeTransport::new();
+    let err = private_client(&transport)
+        .replace_order("d0c5340b-6d6c-49d9-b567-48c4bfca13d2", "36010.00", "one")
+        .await
+        .unwrap_err();
+    assert!(err.is_validation());
+    assert!(transport.requests().is_empty());
+}
+
+const GTT_ORDER_PATH: &str = "/orders/5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39";
+
+fn open_gtt_order() -> String {
+    GTT_ORDER
+        .replace(
+            r#""done_at":"2021-06-19T21:24:20.5Z","done_reason":"canceled","#,
+            "",
+        )
+        .replace(
+            r#""status":"done","settled":true"#,
+            r#""status":"open","settled":false"#,
+        )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_replace_good_till_time_order() {
+    let transport = FakeTransport::new()
+        .respond("GET", GTT_ORDER_PATH, 200, &open_gtt_order())
+        .respond(
+            "DELETE",
+            GTT_ORDER_PATH,
+            200,
+            r#""5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39""#,
+        )
+        .respond("GET", GTT_ORDER_PATH, 404, r#"{"message":"NotFound"}"#)
+        .respond(
+            "POST",
+            "/orders",
+            200,
+            r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+        );
+    let outcome = private_client(&transport)
+        .replace_order("5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39", "35010.00", "0.1")
+        .await
+        .unwrap();
+    assert!(matches!(outcome, ReplaceOutcome::Replaced { .. }));
+    let body = request_body(&transport);
+    assert_eq!(body["time_in_force"], "GTT");
+    // the order expired an hour after it was created
+    assert_eq!(body["cancel_after"], "hour");
+    assert_eq!(body["post_only"], true);
+    assert_eq!(body["price"], "35010.00");
+
+    // no cancel_after expires the order at its expire time
+    let order = open_gtt_order().replace("21:24:20.467086Z", "20:54:20.467086Z");
+    let transport = FakeTransport::new().respond("GET", GTT_ORDER_PATH, 200, &order);
+    let outcome = private_client(&transport)
+        .replace_order("5c1e0ab3-1c84-4a1f-b3d4-6b7e0f1d5e39", "35010.00", "0.1")
         .await
         .unwrap();
+    assert!(matches!(outcome, ReplaceOutcome::NotReplaceable(_)));
+    assert_eq!(transport.requests().len(), 1);
 }
@@ -1,83 +1,236 @@
+use crate::fake_transport::{FakeTransport, PRODUCT, TIME};
+use chrono::{TimeZone, Utc};
+use coinbase_client::private_client::OrderSide;
 use coinbase_client::public_client::*;
+use futures::TryStreamExt;
+
+const CURRENCY: &str = r#"{"id":"LINK","name":"Chainlink","min_size":"0.00000001","status":"online","message":"","max_precision":"0.00000001","convertible_to":[],"details":{"type":"crypto","symbol":"","network_confirmations":35,"sort_order":86,"crypto_address_link":"https://etherscan.io/token/0x514910771af9ca656af840dff83e8264ecf986ca?a={{address}}","crypto_transaction_link":"https://etherscan.io/tx/0x{{txId}}","push_payment_methods":["crypto"],"group_types":[],"display_name":"","processing_time_seconds":0,"min_withdrawal_amount":0.5,"max_withdrawal_amount":48000}}"#;
+
+const TRADES: &str = r#"[{"time":"2021-06-21T17:05:49.250Z","trade_id":30898634,"price":"31957.41","size":"0.00314000","side":"sell"},{"time":"2021-06-21T17:05:48.110Z","trade_id":30898633,"price":"31957.40","size":"0.10000000","side":"buy"}]"#;
+
+fn client(transport: &FakeTransport) -> PublicClient {
+    PublicClient::with_transport(transport.clone())
+}
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_products() {
-    let client = PublicClient::new_sandbox();
-    let _products = client.get_products().await.unwrap();
+    let transport =
+        FakeTransport::new().respond("GET", "/products", 200, &format!("[{}]", PRODUCT));
+    let products = client(&transport).get_products().await.unwrap();
+    assert_eq!(products.len(), 1);
+    assert_eq!(products[0].quote_increment.to_string(), "0.01000000");
+    assert_eq!(transport.last_request().path, "/products");
 }
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product() {
-    let client = PublicClient::new_sandbox();
-    let _product = client.get_product("BTC-USD").await.unwrap();
+    let transport = FakeTransport::new().respond("GET", "/products/BTC-USD", 200, PRODUCT);
+    let product = client(&transport).get_product("BTC-USD").await.unwrap();
+    assert_eq!(product.id, "BTC-USD");
+    assert!(!product.trading_disabled);
 }
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_order_book_all() {
-    let client = PublicClient::new_sandbox();
-    let _order_book = client.get_product_order_book_all("BTC-USD").await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/book?level=3",
+        200,
+        r#"{"sequence":3,"bids":[["295.96","0.05088265","3b0f1225-7f84-490b-a29f-0faef9de823a"]],"asks":[["295.97","5.72036512","da863862-25f4-4868-ac41-005d11ab0a5f"]]}"#,
+    );
+    let order_book = client(&transport)
+        .get_product_order_book_all("BTC-USD")
+        .await
+        .unwrap();
+    assert_eq!(order_book.sequence, 3);
+    assert_eq!(
+        order_book.asks[0].order_id,
+        "da863862-25f4-4868-ac41-005d11ab0a5f"
+    );
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_order_book_top50() {
-    let client = PublicClient::new_sandbox();
-    let _order_book = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/book?level=2",
+        200,
+        r#"{"sequence":3,"bids":[["295.96","4.39088265",2],["295.95","1.0",1]],"asks":[["295.97","25.23542881",12]]}"#,
+    );
+    let order_book = client(&transport)
         .get_product_order_book_top50("BTC-USD")
         .await
         .unwrap();
+    assert_eq!(order_book.bids.len(), 2);
+    assert_eq!(order_book.asks[0].num_orders, 12);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_order_book() {
-    let client = PublicClient::new_sandbox();
-    let _order_book = client.get_product_order_book("BTC-USD").await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/book?level=1",
+        200,
+        r#"{"sequence":3,"bids":[["295.96","4.39088265",2]],"asks":[["295.97","25.23542881",12]]}"#,
+    );
+    let order_book = client(&transport)
+        .get_product_order_book("BTC-USD")
+        .await
+        .unwrap();
+    assert_eq!(order_book.bids[0].price.to_string(), "295.96");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_ticker() {
-    let client = PublicClient::new_sandbox();
-    let _ticker = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/ticker?before=30902419",
+        200,
+        r#"{"trade_id":4729088,"price":"333.99","size":"0.193","bid":"333.98","ask":"333.99","volume":"5957.11914015","time":"2015-11-14T20:46:03.511254Z"}"#,
+    );
+    let ticker = client(&transport)
         .get_product_ticker("BTC-USD", Some("30902419"), None, None)
         .await
         .unwrap();
+    assert_eq!(ticker.trade_id, 4729088);
+    assert_eq!(ticker.ask.to_string(), "333.99");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_trades() {
-    let client = PublicClient::new_sandbox();
-    let _trades = client
+    let transport = FakeTransport::new().respond_with_headers(
+        "GET",
+        "/products/BTC-USD/trades?after=30898635&limit=100",
+        200,
+        &[("cb-before", "30898634"), ("cb-after", "30898633")],
+        TRADES,
+    );
+    let trades = client(&transport)
         .get_product_trades("BTC-USD", None, Some("30898635"), Some(100))
         .await
         .unwrap();
+    assert_eq!(trades.items.len(), 2);
+    assert_eq!(trades.items[1].trade_id, 30898633);
+    assert_eq!(trades.items[0].side, OrderSide::Sell);
+    assert_eq!(trades.before.as_deref(), Some("30898634"));
+    assert_eq!(trades.after.as_deref(), Some("30898633"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_product_trades() {
+    let transport = FakeTransport::new()
+        .respond_with_headers(
+            "GET",
+            "/products/BTC-USD/trades?limit=2",
+            200,
+            &[("cb-before", "30898634"), ("cb-after", "30898633")],
+            TRADES,
+        )
+        .respond_with_headers(
+            "GET",
+            "/products/BTC-USD/trades?after=30898633&limit=2",
+            200,
+            &[("cb-before", "30898632"), ("cb-after", "30898631")],
+            r#"[{"time":"2021-06-21T17:04:00.000Z","trade_id":30898632,"price":"31950.00","size":"1.0","side":"buy"},{"time":"2021-06-21T16:59:00.000Z","trade_id":30898631,"price":"31940.00","size":"1.0","side":"buy"}]"#,
+        )
+        .respond(
+            "GET",
+            "/products/BTC-USD/trades?after=30898631&limit=2",
+            200,
+            "[]",
+        );
+    let client = client(&transport);
+    let trades: Vec<Trade> = client
+        .stream_product_trades("BTC-USD", Some(2), None)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        trades
+            .iter()
+            .map(|trade| trade.trade_id)
+            .collect::<Vec<_>>(),
+        vec![30898634, 30898633, 30898632, 30898631]
+    );
+    assert_eq!(transport.requests().len(), 3);
+
+    // stops at the first trade older than `until`, without requesting the empty page
+    let trades: Vec<Trade> = client
+        .stream_product_trades(
+            "BTC-USD",
+            Some(2),
+            Some(Utc.ymd(2021, 6, 21).and_hms(17, 0, 0)),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(trades.len(), 3);
+    assert_eq!(transport.requests().len(), 5);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_historic_rates() {
-    let client = PublicClient::new_sandbox();
-    let _historical_rates = client
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/candles?granularity=60",
+        200,
+        "[[1415398768,0.32,4.2,0.35,4.2,12.3],[1415398708,0.31,0.35,0.33,0.32,4.1]]",
+    );
+    let historical_rates = client(&transport)
         .get_product_historic_rates("BTC-USD", None, None, Some(Granularity::OneMinute))
         .await
         .unwrap();
+    assert_eq!(historical_rates.len(), 2);
+    assert_eq!(historical_rates[0].time, 1415398768);
+    assert_eq!(historical_rates[0].volume.to_string(), "12.3");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_product_24hr_stats() {
-    let client = PublicClient::new_sandbox();
-    let _twenty_four_hour_stats = client.get_product_24hr_stats("BTC-USD").await.unwrap();
+    let transport = FakeTransport::new().respond(
+        "GET",
+        "/products/BTC-USD/stats",
+        200,
+        r#"{"open":"6745.61000000","high":"7292.11000000","low":"6650.00000000","volume":"26185.51325269","last":"6813.19000000","volume_30day":"1019451.11188405"}"#,
+    );
+    let twenty_four_hour_stats = client(&transport)
+        .get_product_24hr_stats("BTC-USD")
+        .await
+        .unwrap();
+    assert_eq!(twenty_four_hour_stats.last.to_string(), "6813.19000000");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_currencies() {
-    let client = PublicClient::new_sandbox();
-    let _currencies = client.get_currencies().await.unwrap();
+    let transport =
+        FakeTransport::new().respond("GET", "/currencies", 200, &format!("[{}]", CURRENCY));
+    let currencies = client(&transport).get_currencies().await.unwrap();
+    assert_eq!(currencies[0].details.network_confirmations, 35);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_currency() {
-    let client = PublicClient::new_sandbox();
-    let _currency = client.get_currency("LINK").await.unwrap();
+    let transport = FakeTransport::new().respond("GET", "/currencies/LINK", 200, CURRENCY);
+    let currency = client(&transport).get_currency("LINK").await.unwrap();
+    assert_eq!(currency.name, "Chainlink");
+    assert_eq!(currency.details.max_withdrawal_amount.to_string(), "48000");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_get_time() {
-    let client = PublicClient::new_sandbox();
-    let _time = client.get_time().await.unwrap();
+    let transport = FakeTransport::new().respond("GET", "/time", 200, TIME);
+    let time = client(&transport).get_time().await.unwrap();
+    assert_eq!(time.iso, Utc.ymd(2015, 1, 7).and_hms_milli(23, 47, 25, 201));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_status_error() {
+    let transport =
+        FakeTransport::new().respond("GET", "/products/FOO-BAR", 404, r#"{"message":"NotFound"}"#);
+    let error = client(&transport).get_product("FOO-BAR").await.unwrap_err();
+    match error.kind {
+        coinbase_client::error::ErrorKind::Status(status) => {
+            assert_eq!(status.code, 404);
+            assert_eq!(status.message, "NotFound");
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
 }
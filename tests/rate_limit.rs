@@ -0,0 +1,62 @@
+use crate::fake_transport::{FakeTransport, TIME};
+use coinbase_client::public_client::PublicClient;
+use coinbase_client::rate_limit::RateLimiter;
+use futures::future;
+use std::time::{Duration, Instant};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_burst() {
+    let limiter = RateLimiter::new(10.0, 3);
+    assert_eq!(limiter.available(), 3);
+    for _ in 0..3 {
+        assert!(limiter.try_acquire());
+    }
+    assert_eq!(limiter.available(), 0);
+    assert!(!limiter.try_acquire());
+    assert!(limiter.wait_time() > Duration::from_millis(50));
+    assert!(limiter.wait_time() <= Duration::from_millis(100));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_acquire_waits() {
+    let limiter = RateLimiter::new(20.0, 2);
+    let start = Instant::now();
+    // the burst passes, the next two wait 50ms each
+    for _ in 0..4 {
+        limiter.acquire().await;
+    }
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(90), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(500), "{:?}", elapsed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_refill() {
+    let limiter = RateLimiter::new(50.0, 2);
+    assert!(limiter.try_acquire());
+    assert!(limiter.try_acquire());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    // refills up to the burst and no further
+    assert_eq!(limiter.available(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_shared_across_clones() {
+    let transport = FakeTransport::new().respond("GET", "/time", 200, TIME);
+    let limiter = RateLimiter::new(20.0, 2);
+    let client = PublicClient::with_transport(transport.clone()).with_rate_limiter(limiter.clone());
+    let clone = client.clone();
+    let start = Instant::now();
+    let results = future::join_all(vec![
+        client.get_time(),
+        clone.get_time(),
+        client.get_time(),
+        clone.get_time(),
+    ])
+    .await;
+    assert!(results.iter().all(Result::is_ok));
+    assert!(start.elapsed() >= Duration::from_millis(90));
+    assert_eq!(transport.requests().len(), 4);
+    assert_eq!(limiter.available(), 0);
+    assert_eq!(clone.rate_limiter().unwrap().available(), 0);
+}
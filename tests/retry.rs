@@ -0,0 +1,191 @@
+use crate::fake_transport::{private_client, FakeTransport, ORDER, TIME};
+use coinbase_client::client_builder::ClientBuilder;
+use coinbase_client::private_client::*;
+use coinbase_client::public_client::PublicClient;
+use coinbase_client::retry::RetryPolicy;
+use coinbase_client::transport::Method;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const ORDER_ID: &str = r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#;
+
+const CLIENT_OID: &str = "1f6a7175-a89c-494f-986d-af9987e6dd69";
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+    }
+}
+
+fn retrying_client(transport: &FakeTransport) -> PrivateClient {
+    private_client(transport).with_retry_policy(policy())
+}
+
+fn order(client_oid: Option<&str>) -> Order {
+    let builder = OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000.0", "1.0");
+    match client_oid {
+        Some(client_oid) => builder.client_oid(client_oid.to_string()).build(),
+        None => builder.build(),
+    }
+}
+
+fn count(transport: &FakeTransport, method: Method) -> usize {
+    transport
+        .requests()
+        .iter()
+        .filter(|request| request.method == method)
+        .count()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_get() {
+    let transport = FakeTransport::new()
+        .respond("GET", "/time", 503, r#"{"message":"Service Unavailable"}"#)
+        .respond(
+            "GET",
+            "/time",
+            500,
+            r#"{"message":"Internal Server Error"}"#,
+        )
+        .respond("GET", "/time", 200, TIME);
+    let client = PublicClient::with_transport(transport.clone()).with_retry_policy(policy());
+    client.get_time().await.unwrap();
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_gives_up() {
+    let transport =
+        FakeTransport::new().respond("GET", "/time", 503, r#"{"message":"Service Unavailable"}"#);
+    let client = PublicClient::with_transport(transport.clone()).with_retry_policy(policy());
+    let err = client.get_time().await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "status code: 503, message: Service Unavailable (GET /time)"
+    );
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_no_retry_on_client_error() {
+    let transport =
+        FakeTransport::new().respond("GET", "/accounts", 400, r#"{"message":"Invalid Request"}"#);
+    retrying_client(&transport)
+        .get_accounts()
+        .await
+        .unwrap_err();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_after() {
+    let transport = FakeTransport::new()
+        .respond_with_headers(
+            "GET",
+            "/time",
+            429,
+            &[("retry-after", "0")],
+            r#"{"message":"Rate limit exceeded"}"#,
+        )
+        .respond("GET", "/time", 200, TIME);
+    // the backoff would take far longer than the test
+    let client = PublicClient::with_transport(transport.clone()).with_retry_policy(RetryPolicy {
+        max_retries: 1,
+        initial_backoff: Duration::from_secs(30),
+        max_backoff: Duration::from_secs(30),
+    });
+    let start = Instant::now();
+    client.get_time().await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_after_is_capped() {
+    let transport = FakeTransport::new()
+        .respond_with_headers(
+            "GET",
+            "/time",
+            503,
+            &[("retry-after", "86400")],
+            r#"{"message":"Service unavailable"}"#,
+        )
+        .respond("GET", "/time", 200, TIME);
+    let client = PublicClient::with_transport(transport.clone()).with_retry_policy(policy());
+    let start = Instant::now();
+    client.get_time().await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_without_client_oid_is_not_retried() {
+    let transport = FakeTransport::new().respond(
+        "POST",
+        "/orders",
+        503,
+        r#"{"message":"Service Unavailable"}"#,
+    );
+    let client = retrying_client(&transport);
+    client.place_order(order(None)).await.unwrap_err();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_resubmits_missing_order() {
+    let transport = FakeTransport::new()
+        .respond("POST", "/orders", 502, r#"{"message":"Bad Gateway"}"#)
+        .respond("POST", "/orders", 200, ORDER_ID)
+        .respond(
+            "GET",
+            &format!("/orders/client:{}", CLIENT_OID),
+            404,
+            r#"{"message":"NotFound"}"#,
+        );
+    let client = retrying_client(&transport);
+    let id = client.place_order(order(Some(CLIENT_OID))).await.unwrap();
+    assert_eq!(id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    assert_eq!(count(&transport, Method::POST), 2);
+    assert_eq!(count(&transport, Method::GET), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_order_finds_placed_order() {
+    let transport = FakeTransport::new()
+        .respond("POST", "/orders", 504, r#"{"message":"Gateway Timeout"}"#)
+        .respond("GET", &format!("/orders/client:{}", CLIENT_OID), 200, ORDER);
+    let client = retrying_client(&transport);
+    let id = client.place_order(order(Some(CLIENT_OID))).await.unwrap();
+    assert_eq!(id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    // never submitted twice
+    assert_eq!(count(&transport, Method::POST), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_connection_reset() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        // the first connection is closed without a response
+        let (stream, _) = listener.accept().await.unwrap();
+        drop(stream);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0; 1024];
+        let _ = stream.read(&mut buf).await;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            TIME.len(),
+            TIME
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    });
+    let client = ClientBuilder::new()
+        .base_url(&url)
+        .retry_policy(policy())
+        .build_public()
+        .unwrap();
+    client.get_time().await.unwrap();
+}
@@ -0,0 +1,154 @@
+use crate::fake_transport::PRODUCT;
+use coinbase_client::error::{ErrorKind, ValidationError};
+use coinbase_client::private_client::*;
+use coinbase_client::public_client::Product;
+use coinbase_client::trading_rules::{Rounding, TradingRules};
+
+fn rules() -> TradingRules {
+    let product: Product = serde_json::from_str(PRODUCT).unwrap();
+    product.trading_rules().unwrap()
+}
+
+#[test]
+fn test_round_price() {
+    let rules = rules();
+    assert_eq!(rules.product_id(), "BTC-USD");
+    let price = 36000.0 * 1.0015;
+    assert_eq!(
+        rules.round_price(price, Rounding::Down).unwrap().as_str(),
+        "36054.00"
+    );
+    assert_eq!(
+        rules
+            .round_price("36054.001", Rounding::Up)
+            .unwrap()
+            .as_str(),
+        "36054.01"
+    );
+    assert_eq!(
+        rules
+            .round_price("36054.005", Rounding::Nearest)
+            .unwrap()
+            .as_str(),
+        "36054.01"
+    );
+    assert_eq!(
+        rules
+            .round_price("36054.0049", Rounding::Nearest)
+            .unwrap()
+            .as_str(),
+        "36054.00"
+    );
+    assert_eq!(
+        rules.round_price("36054", Rounding::Up).unwrap().as_str(),
+        "36054.00"
+    );
+    // 0.30000000000000004 as a float
+    assert_eq!(
+        rules
+            .round_price(0.1 + 0.2, Rounding::Nearest)
+            .unwrap()
+            .as_str(),
+        "0.30"
+    );
+}
+
+#[test]
+fn test_round_floats() {
+    let rules = rules();
+    assert_eq!(
+        rules
+            .round_price(36000.123456789, Rounding::Nearest)
+            .unwrap()
+            .as_str(),
+        "36000.12"
+    );
+    assert_eq!(
+        rules
+            .round_limit_price(&OrderSide::Sell, 36000.123456789)
+            .unwrap()
+            .as_str(),
+        "36000.13"
+    );
+    assert_eq!(
+        rules.round_size(0.123456789123).unwrap().as_str(),
+        "0.12345678"
+    );
+    assert_eq!(rules.round_funds(99.999).unwrap().as_str(), "99.99");
+}
+
+#[test]
+fn test_round_limit_price() {
+    let rules = rules();
+    assert_eq!(
+        rules
+            .round_limit_price(&OrderSide::Buy, "36054.009")
+            .unwrap()
+            .as_str(),
+        "36054.00"
+    );
+    assert_eq!(
+        rules
+            .round_limit_price(&OrderSide::Sell, "36054.001")
+            .unwrap()
+            .as_str(),
+        "36054.01"
+    );
+}
+
+#[test]
+fn test_round_size_and_funds() {
+    let rules = rules();
+    assert_eq!(
+        rules.round_size(100.0 / 36054.0).unwrap().as_str(),
+        "0.00277361"
+    );
+    assert_eq!(
+        rules.round_size("1.999999999").unwrap().as_str(),
+        "1.99999999"
+    );
+    assert_eq!(rules.round_funds("10.019").unwrap().as_str(), "10.01");
+}
+
+#[test]
+fn test_round_invalid_number() {
+    let err = rules().round_size(f64::NAN).unwrap_err();
+    match err.kind {
+        ErrorKind::Validation(err) => assert_eq!(
+            err,
+            ValidationError::InvalidNumber {
+                field: "size",
+                value: "NaN".to_string(),
+            }
+        ),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn test_builder_round_to() {
+    let order = OrderBuilder::limit(OrderSide::Sell, "BTC-USD", "36054.001", "0.123456789")
+        .round_to(rules())
+        .unwrap()
+        .build();
+    assert_eq!(
+        serde_json::to_value(&order).unwrap(),
+        serde_json::json!({
+            "type": "limit",
+            "side": "sell",
+            "product_id": "BTC-USD",
+            "price": "36054.01",
+            "size": "0.12345678",
+        })
+    );
+    let result = OrderBuilder::market(
+        OrderSide::Buy,
+        "BTC-USD",
+        SizeOrFunds::Funds("not a number".to_string()),
+    )
+    .round_to(rules());
+    assert!(matches!(
+        result.err().unwrap().kind,
+        ErrorKind::Validation(ValidationError::InvalidNumber { field: "funds", .. })
+    ));
+}
@@ -0,0 +1,235 @@
+use crate::fake_transport::{private_client, FakeTransport, PRODUCT};
+use coinbase_client::error::{ErrorKind, ValidationError};
+use coinbase_client::private_client::*;
+use coinbase_client::public_client::Product;
+use coinbase_client::transport::Method;
+
+fn product(mode: Option<&str>) -> Product {
+    let product = match mode {
+        Some(mode) => PRODUCT.replace(
+            &format!(r#""{}":false"#, mode),
+            &format!(r#""{}":true"#, mode),
+        ),
+        None => PRODUCT.to_string(),
+    };
+    serde_json::from_str(&product).unwrap()
+}
+
+fn validation_error(order: Order, product: &Product) -> ValidationError {
+    match order.validate(product).unwrap_err().kind {
+        ErrorKind::Validation(err) => err,
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+fn limit(price: &str, size: &str) -> Order {
+    OrderBuilder::limit(OrderSide::Buy, "BTC-USD", price, size).build()
+}
+
+fn market_funds(funds: &str) -> Order {
+    OrderBuilder::market(
+        OrderSide::Buy,
+        "BTC-USD",
+        SizeOrFunds::Funds(funds.to_string()),
+    )
+    .build()
+}
+
+#[test]
+fn test_valid_orders() {
+    let product = product(None);
+    limit("36000.01", "0.00123456").validate(&product).unwrap();
+    market_funds("10.00").validate(&product).unwrap();
+    OrderBuilder::stop_limit(
+        OrderSide::Sell,
+        "BTC-USD",
+        OrderStop::Loss,
+        "31000.00",
+        "30900.5",
+        "280",
+    )
+    .build()
+    .validate(&product)
+    .unwrap();
+}
+
+#[test]
+fn test_increments() {
+    let product = product(None);
+    let err = validation_error(limit("36000.005", "1"), &product);
+    assert_eq!(
+        err,
+        ValidationError::Increment {
+            field: "price",
+            value: "36000.005".to_string(),
+            increment: "0.01000000".to_string(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "price 36000.005 is not a multiple of 0.01000000"
+    );
+    assert!(matches!(
+        validation_error(limit("36000", "0.000000001"), &product),
+        ValidationError::Increment { field: "size", .. }
+    ));
+    assert!(matches!(
+        validation_error(market_funds("10.001"), &product),
+        ValidationError::Increment { field: "funds", .. }
+    ));
+    let stop = OrderBuilder::stop_market(
+        OrderSide::Sell,
+        "BTC-USD",
+        OrderStop::Loss,
+        "31000.001",
+        SizeOrFunds::size("1"),
+    )
+    .build();
+    assert!(matches!(
+        validation_error(stop, &product),
+        ValidationError::Increment {
+            field: "stop_price",
+            ..
+        }
+    ));
+    let stop = OrderBuilder::stop_limit(
+        OrderSide::Sell,
+        "BTC-USD",
+        OrderStop::Loss,
+        "31000.00",
+        "30900.005",
+        "1",
+    )
+    .build();
+    assert!(matches!(
+        validation_error(stop, &product),
+        ValidationError::Increment {
+            field: "stop_limit_price",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_limits() {
+    let product = product(None);
+    assert_eq!(
+        validation_error(limit("36000", "0.0009"), &product),
+        ValidationError::BelowMinimum {
+            field: "size",
+            value: "0.0009".to_string(),
+            minimum: "0.00100000".to_string(),
+        }
+    );
+    assert!(matches!(
+        validation_error(limit("36000", "280.00000001"), &product),
+        ValidationError::AboveMaximum { field: "size", .. }
+    ));
+    assert!(matches!(
+        validation_error(market_funds("4.99"), &product),
+        ValidationError::BelowMinimum { field: "funds", .. }
+    ));
+    assert!(matches!(
+        validation_error(market_funds("1000000.01"), &product),
+        ValidationError::AboveMaximum { field: "funds", .. }
+    ));
+    assert!(matches!(
+        validation_error(limit("0", "1"), &product),
+        ValidationError::BelowMinimum { field: "price", .. }
+    ));
+    assert_eq!(
+        validation_error(limit("36,000", "1"), &product),
+        ValidationError::InvalidNumber {
+            field: "price",
+            value: "36,000".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_trading_modes() {
+    assert_eq!(
+        validation_error(limit("36000", "1"), &product(Some("trading_disabled"))),
+        ValidationError::TradingDisabled
+    );
+    assert_eq!(
+        validation_error(limit("36000", "1"), &product(Some("cancel_only"))),
+        ValidationError::CancelOnly
+    );
+    assert_eq!(
+        validation_error(market_funds("10"), &product(Some("limit_only"))),
+        ValidationError::LimitOnly
+    );
+    limit("36000", "1")
+        .validate(&product(Some("limit_only")))
+        .unwrap();
+
+    let post_only = product(Some("post_only"));
+    assert_eq!(
+        validation_error(limit("36000", "1"), &post_only),
+        ValidationError::PostOnly
+    );
+    assert_eq!(
+        validation_error(market_funds("10"), &post_only),
+        ValidationError::PostOnly
+    );
+    OrderBuilder::limit(OrderSide::Buy, "BTC-USD", "36000", "1")
+        .time_in_force(TimeInForce::GoodTillCancel { post_only: true })
+        .build()
+        .validate(&post_only)
+        .unwrap();
+}
+
+#[test]
+fn test_product_mismatch() {
+    let order = OrderBuilder::limit(OrderSide::Buy, "ETH-USD", "2000", "1").build();
+    let error = order.validate(&product(None)).unwrap_err();
+    assert!(error.is_validation());
+    assert_eq!(
+        error.to_string(),
+        "validation error: order for ETH-USD validated against BTC-USD"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_place_validated_order() {
+    let transport = FakeTransport::new()
+        .respond("GET", "/products/BTC-USD", 200, PRODUCT)
+        .respond(
+            "POST",
+            "/orders",
+            200,
+            r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2"}"#,
+        );
+    let client = private_client(&transport);
+    let id = client
+        .place_validated_order(limit("36000.01", "1"))
+        .await
+        .unwrap();
+    assert_eq!(id, "d0c5340b-6d6c-49d9-b567-48c4bfca13d2");
+    let error = client
+        .place_validated_order(limit("36000.001", "1"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::Validation(_)));
+    client
+        .place_validated_order(market_funds("10"))
+        .await
+        .unwrap();
+    let requests = transport.requests();
+    // the product is fetched once and the invalid order is never sent
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|request| request.method == Method::GET)
+            .count(),
+        1
+    );
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|request| request.method == Method::POST)
+            .count(),
+        2
+    );
+}
@@ -0,0 +1,432 @@
+use crate::fake_transport::SECRET;
+use chrono::{TimeZone, Utc};
+use coinbase_client::private_client::{OrderSide, PrivateClient};
+use coinbase_client::websocket::*;
+use crypto::mac::Mac;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::WebSocketStream;
+
+// frames recorded from the websocket feed
+const FRAMES: &[&str] = &[
+    r#"{"type":"subscriptions","channels":[{"name":"heartbeat","product_ids":["BTC-USD"]},{"name":"ticker","product_ids":["BTC-USD"]},{"name":"level2","product_ids":["BTC-USD"]},{"name":"full","product_ids":["BTC-USD"]}]}"#,
+    r#"{"type":"heartbeat","last_trade_id":20153558,"product_id":"BTC-USD","sequence":3262786978,"time":"2021-06-21T17:05:49.250000Z"}"#,
+    r#"{"type":"ticker","sequence":3262786978,"product_id":"BTC-USD","price":"4388.01000000","open_24h":"4350.00000000","volume_24h":"9131.58317410","low_24h":"4302.00000000","high_24h":"4420.88000000","volume_30d":"342102.89562458","best_bid":"4388.00","best_ask":"4388.01","side":"buy","time":"2021-06-21T17:05:49.250000Z","trade_id":20153558,"last_size":"0.03000000"}"#,
+    r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["10101.10","0.45054140"]],"asks":[["10102.55","0.57753524"]]}"#,
+    r#"{"type":"l2update","product_id":"BTC-USD","time":"2021-06-21T17:05:49.301000Z","changes":[["buy","10101.80","0.162567"],["sell","10102.55","0"]]}"#,
+    r#"{"type":"received","time":"2021-06-21T17:05:49.350000Z","product_id":"BTC-USD","sequence":10,"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","size":"1.34","price":"502.1","side":"buy","order_type":"limit"}"#,
+    r#"{"type":"open","time":"2021-06-21T17:05:49.360000Z","product_id":"BTC-USD","sequence":11,"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","price":"502.1","remaining_size":"1.00","side":"sell"}"#,
+    r#"{"type":"match","trade_id":10,"sequence":12,"maker_order_id":"ac928c66-ca53-498f-9c13-a110027a60e8","taker_order_id":"132fb6ae-456b-4654-b4e0-d681ac05cea1","time":"2021-06-21T17:05:49.370000Z","product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell"}"#,
+    r#"{"type":"done","time":"2021-06-21T17:05:49.380000Z","product_id":"BTC-USD","sequence":13,"price":"200.2","order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","reason":"filled","side":"sell","remaining_size":"0"}"#,
+    r#"{"type":"change","time":"2021-06-21T17:05:49.390000Z","sequence":14,"order_id":"ac928c66-ca53-498f-9c13-a110027a60e8","product_id":"BTC-USD","new_size":"5.23512","old_size":"12.234412","price":"400.23","side":"sell"}"#,
+    r#"{"type":"error","message":"Failed to subscribe","reason":"FOO-BAR is not a valid product"}"#,
+];
+
+// frames recorded from the authenticated user channel
+const USER_FRAMES: &[&str] = &[
+    r#"{"type":"match","trade_id":10,"sequence":50,"maker_order_id":"ac928c66-ca53-498f-9c13-a110027a60e8","taker_order_id":"132fb6ae-456b-4654-b4e0-d681ac05cea1","time":"2021-06-21T17:05:49.370000Z","product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell","taker_user_id":"5844eceecf7e803e259d0365","user_id":"5844eceecf7e803e259d0365","taker_profile_id":"765d1549-9660-4be2-97d4-fa2d65fa3352","profile_id":"765d1549-9660-4be2-97d4-fa2d65fa3352"}"#,
+];
+
+// replays `frames` to the first client once it subscribes, then forwards the next request and closes
+async fn replay_server(
+    frames: &'static [&'static str],
+) -> (String, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+        let text = next_text(&mut ws).await.unwrap();
+        sender.send(text).unwrap();
+        ws.send(WsMessage::Ping(vec![1])).await.unwrap();
+        for frame in frames {
+            ws.send(WsMessage::Text(frame.to_string())).await.unwrap();
+        }
+        if let Some(text) = next_text(&mut ws).await {
+            sender.send(text).unwrap();
+        }
+        ws.close(None).await.unwrap();
+    });
+    (url, receiver)
+}
+
+// accepts a client for each entry of `requests`, forwards that many requests and sends a heartbeat
+// then drops the socket without a close frame, or keeps it open without sending anything if `hold` is set
+async fn dropping_server(
+    requests: &'static [usize],
+    hold: bool,
+) -> (String, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        for count in requests {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            for _ in 0..*count {
+                sender.send(next_text(&mut ws).await.unwrap()).unwrap();
+            }
+            ws.send(WsMessage::Text(FRAMES[1].to_string()))
+                .await
+                .unwrap();
+            if hold {
+                held.push(ws);
+            }
+        }
+        // refuse further connections
+        drop(listener);
+        drop(held);
+    });
+    (url, receiver)
+}
+
+// skips pong frames sent in reply to the server's ping
+async fn next_text<S>(ws: &mut WebSocketStream<S>) -> Option<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    while let Some(Ok(message)) = ws.next().await {
+        if let WsMessage::Text(text) = message {
+            return Some(text);
+        }
+    }
+    None
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_subscribe() {
+    let (url, mut requests) = replay_server(FRAMES).await;
+    let mut feed = WebsocketFeed::connect_to(&url).await.unwrap();
+    feed.subscribe(
+        &["BTC-USD"],
+        &[
+            Channel::Heartbeat,
+            Channel::Ticker,
+            Channel::Level2,
+            Channel::Full,
+        ],
+    )
+    .await
+    .unwrap();
+    let request: serde_json::Value = serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+    assert_eq!(
+        request,
+        serde_json::json!({
+            "type": "subscribe",
+            "product_ids": ["BTC-USD"],
+            "channels": ["heartbeat", "ticker", "level2", "full"]
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_subscribe_authenticated() {
+    let client = PrivateClient::new(
+        SECRET.to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+    );
+    let (url, mut requests) = replay_server(USER_FRAMES).await;
+    let mut feed = WebsocketFeed::connect_to(&url).await.unwrap();
+    feed.subscribe_authenticated(&["BTC-USD"], &[Channel::User], &client)
+        .await
+        .unwrap();
+    let request: serde_json::Value = serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+    assert_eq!(request["type"], "subscribe");
+    assert_eq!(request["channels"], serde_json::json!(["user"]));
+    assert_eq!(request["key"], "4a9f6de8bcdee641a0a207613dfb43ef");
+    assert_eq!(request["passphrase"], "t9riylyad0r");
+
+    let timestamp = request["timestamp"].as_str().unwrap();
+    let mut hmac = crypto::hmac::Hmac::new(
+        crypto::sha2::Sha256::new(),
+        &base64::decode(SECRET).unwrap(),
+    );
+    hmac.input(format!("{}GET/users/self/verify", timestamp).as_bytes());
+    assert_eq!(request["signature"], base64::encode(hmac.result().code()));
+
+    match feed.next().await.unwrap().unwrap() {
+        Message::Match(m) => {
+            assert_eq!(m.taker_user_id.as_deref(), Some("5844eceecf7e803e259d0365"));
+            assert_eq!(m.user_id.as_deref(), Some("5844eceecf7e803e259d0365"));
+            assert_eq!(
+                m.profile_id.as_deref(),
+                Some("765d1549-9660-4be2-97d4-fa2d65fa3352")
+            );
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_unsubscribe() {
+    let (url, mut requests) = replay_server(FRAMES).await;
+    let mut feed = WebsocketFeed::connect_to(&url).await.unwrap();
+    feed.subscribe(&["BTC-USD"], &[Channel::Matches])
+        .await
+        .unwrap();
+    let _subscribe = requests.recv().await.unwrap();
+    let _messages: Vec<_> = feed.by_ref().take(FRAMES.len()).collect().await;
+    feed.unsubscribe(&["BTC-USD"], &[Channel::Matches])
+        .await
+        .unwrap();
+    let request: serde_json::Value = serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+    assert_eq!(
+        request,
+        serde_json::json!({
+            "type": "unsubscribe",
+            "product_ids": ["BTC-USD"],
+            "channels": ["matches"]
+        })
+    );
+    assert!(feed.next().await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_messages() {
+    let (url, _requests) = replay_server(FRAMES).await;
+    let mut feed = WebsocketFeed::connect_to(&url).await.unwrap();
+    feed.subscribe(&["BTC-USD"], &[Channel::Full])
+        .await
+        .unwrap();
+    let messages: Vec<Message> = feed
+        .by_ref()
+        .take(FRAMES.len())
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    match &messages[0] {
+        Message::Subscriptions(subscriptions) => {
+            assert_eq!(subscriptions.channels.len(), 4);
+            assert_eq!(subscriptions.channels[2].name, Channel::Level2);
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+    match &messages[1] {
+        Message::Heartbeat(heartbeat) => {
+            assert_eq!(heartbeat.last_trade_id, 20153558);
+            assert_eq!(
+                heartbeat.time,
+                Utc.ymd(2021, 6, 21).and_hms_milli(17, 5, 49, 250)
+            );
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+    match &messages[2] {
+        Message::Ticker(ticker) => {
+            assert_eq!(ticker.price.to_string(), "4388.01000000");
+            assert_eq!(ticker.side, Some(OrderSide::Buy));
+            assert_eq!(ticker.trade_id, Some(20153558));
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+    match &messages[3] {
+        Message::Snapshot(snapshot) => {
+            assert_eq!(snapshot.bids[0].price.to_string(), "10101.10");
+            assert_eq!(snapshot.asks[0].size.to_string(), "0.57753524");
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+    match &messages[4] {
+        Message::Level2Update(update) => {
+            assert_eq!(update.changes.len(), 2);
+            assert_eq!(update.changes[1].side, OrderSide::Sell);
+            assert_eq!(update.changes[1].size.to_string(), "0");
+        }
+        message => panic!("unexpected message {:?}", message),
+    }
+    assert!(matches!(&messages[5], Message::Received(received) if received.order_type == "limit"));
+    match &messages[6] {
+        Message::Open(open) => assert_eq!(open.remaining_size.to_string(), "1.00"),
+        message => panic!("unexpected message {:?}", message),
+    }
+    assert!(matches!(&messages[7], Message::Match(m) if m.trade_id == 10));
+    assert!(matches!(&messages[8], Message::Done(done) if done.reason == DoneReason::Filled));
+    assert!(matches!(&messages[9], Message::Change(change) if change.sequence == 14));
+    assert!(
+        matches!(&messages[10], Message::Error(error) if error.message == "Failed to subscribe")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_reconnect() {
+    let (url, mut requests) = dropping_server(&[2, 2], false).await;
+    let mut feed = WebsocketFeed::connect_to(&url)
+        .await
+        .unwrap()
+        .with_reconnect(ReconnectOptions {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            heartbeat_timeout: Duration::from_secs(10),
+            max_attempts: Some(2),
+        });
+    feed.subscribe(
+        &["BTC-USD", "ETH-USD"],
+        &[Channel::Heartbeat, Channel::Ticker],
+    )
+    .await
+    .unwrap();
+    feed.unsubscribe(&["ETH-USD"], &[Channel::Ticker])
+        .await
+        .unwrap();
+    let _subscribe = requests.recv().await.unwrap();
+    let _unsubscribe = requests.recv().await.unwrap();
+
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Disconnected))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Reconnected))));
+    // the active subscriptions are replayed on the new connection
+    let replayed: Vec<serde_json::Value> = vec![
+        serde_json::from_str(&requests.recv().await.unwrap()).unwrap(),
+        serde_json::from_str(&requests.recv().await.unwrap()).unwrap(),
+    ];
+    assert_eq!(
+        replayed,
+        vec![
+            serde_json::json!({
+                "type": "subscribe",
+                "product_ids": ["BTC-USD", "ETH-USD"],
+                "channels": ["heartbeat"]
+            }),
+            serde_json::json!({
+                "type": "subscribe",
+                "product_ids": ["BTC-USD"],
+                "channels": ["ticker"]
+            }),
+        ]
+    );
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Disconnected))));
+    // the server is gone, the feed gives up after `max_attempts`
+    assert!(matches!(feed.next().await, Some(Err(_))));
+    assert!(feed.next().await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_subscribe_while_reconnecting() {
+    let (url, mut requests) = dropping_server(&[1, 3], false).await;
+    let mut feed = WebsocketFeed::connect_to(&url)
+        .await
+        .unwrap()
+        .with_reconnect(ReconnectOptions {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(100),
+            heartbeat_timeout: Duration::from_secs(10),
+            max_attempts: Some(1),
+        });
+    feed.subscribe(&["BTC-USD", "ETH-USD"], &[Channel::Heartbeat])
+        .await
+        .unwrap();
+    let _subscribe = requests.recv().await.unwrap();
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Disconnected))));
+
+    // changes while reconnecting do not restart the attempt
+    for _ in 0..3 {
+        feed.subscribe(&["BTC-USD"], &[Channel::Ticker])
+            .await
+            .unwrap();
+    }
+    feed.unsubscribe(&["ETH-USD"], &[Channel::Heartbeat])
+        .await
+        .unwrap();
+    assert!(matches!(feed.next().await, Some(Ok(Message::Reconnected))));
+    let mut replayed = Vec::new();
+    for _ in 0..3 {
+        let request: serde_json::Value =
+            serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+        replayed.push(request);
+    }
+    assert_eq!(
+        replayed,
+        vec![
+            serde_json::json!({
+                "type": "subscribe",
+                "product_ids": ["BTC-USD", "ETH-USD"],
+                "channels": ["heartbeat"]
+            }),
+            serde_json::json!({
+                "type": "unsubscribe",
+                "product_ids": ["ETH-USD"],
+                "channels": ["heartbeat"]
+            }),
+            serde_json::json!({
+                "type": "subscribe",
+                "product_ids": ["BTC-USD"],
+                "channels": ["ticker"]
+            }),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_reconnect_missed_heartbeats() {
+    let (url, mut requests) = dropping_server(&[1, 1], true).await;
+    let mut feed = WebsocketFeed::connect_to(&url)
+        .await
+        .unwrap()
+        .with_reconnect(ReconnectOptions {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            heartbeat_timeout: Duration::from_millis(200),
+            max_attempts: None,
+        });
+    feed.subscribe(&["BTC-USD"], &[Channel::Heartbeat])
+        .await
+        .unwrap();
+    let _subscribe = requests.recv().await.unwrap();
+
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    // the first connection stays open but goes quiet
+    assert!(matches!(feed.next().await, Some(Ok(Message::Disconnected))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Reconnected))));
+    let replayed: serde_json::Value =
+        serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+    assert_eq!(replayed["channels"], serde_json::json!(["heartbeat"]));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    feed.close().await.unwrap();
+    assert!(feed.next().await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_failed_subscribe_is_not_replayed() {
+    let (url, mut requests) = dropping_server(&[1, 1], false).await;
+    let mut feed = WebsocketFeed::connect_to(&url)
+        .await
+        .unwrap()
+        .with_reconnect(ReconnectOptions {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            heartbeat_timeout: Duration::from_secs(10),
+            max_attempts: None,
+        });
+    feed.subscribe(&["BTC-USD"], &[Channel::Heartbeat])
+        .await
+        .unwrap();
+    let _subscribe = requests.recv().await.unwrap();
+    // the request cannot be signed
+    let client = PrivateClient::new(
+        "not base64!".to_string(),
+        "t9riylyad0r".to_string(),
+        "4a9f6de8bcdee641a0a207613dfb43ef".to_string(),
+    );
+    assert!(feed
+        .subscribe_authenticated(&["BTC-USD"], &[Channel::User], &client)
+        .await
+        .is_err());
+
+    assert!(matches!(feed.next().await, Some(Ok(Message::Heartbeat(_)))));
+    assert!(matches!(feed.next().await, Some(Ok(Message::Disconnected))));
+    // only the subscription that was sent is replayed
+    assert!(matches!(feed.next().await, Some(Ok(Message::Reconnected))));
+    let replayed: serde_json::Value =
+        serde_json::from_str(&requests.recv().await.unwrap()).unwrap();
+    assert_eq!(replayed["channels"], serde_json::json!(["heartbeat"]));
+}